@@ -0,0 +1,4193 @@
+//! Scraper de pages Wikipedia : recherche, téléchargement, extraction des
+//! données d'un article et export (JSON, Markdown, TXT, CSV, SQLite).
+//!
+//! Le binaire `wikipedia_scraper` n'est qu'une interface en ligne de commande
+//! autour de ces fonctions.
+
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use indicatif::ProgressBar;
+use scraper::{Html, Selector, ElementRef};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use rustls::pki_types::ServerName;
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use sanitize_filename::sanitize;
+
+/// Niveau de verbosité de la console : 0 = `--quiet`, 1 = normal, 2 = `--verbose`
+static VERBOSITY: AtomicU8 = AtomicU8::new(1);
+
+/// Niveau de verbosité courant (utilisé par les macros `info!`, `warn!` et `debug!`)
+pub fn verbosity() -> u8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Change le niveau de verbosité : 0 = erreurs seulement, 1 = normal, 2 = détaillé
+pub fn set_verbosity(level: u8) {
+    VERBOSITY.store(level, Ordering::Relaxed);
+}
+
+/// Barre de progression du binaire : tant qu'elle est affichée, `info!` se tait et
+/// `warn!` s'affiche au-dessus d'elle
+static PROGRESS_BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// Déclare (ou retire, avec `None`) la barre de progression affichée
+pub fn set_progress_bar(bar: Option<ProgressBar>) {
+    *PROGRESS_BAR.lock().unwrap_or_else(|e| e.into_inner()) = bar;
+}
+
+/// Indique si une barre de progression remplace les messages d'état
+pub fn progress_active() -> bool {
+    PROGRESS_BAR.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+}
+
+/// Écrit un avertissement sur stderr sans abîmer la barre de progression (utilisé par `warn!`)
+#[doc(hidden)]
+pub fn print_warning(message: std::fmt::Arguments) {
+    let bar = PROGRESS_BAR.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    match bar {
+        Some(bar) => bar.suspend(|| eprintln!("{}", message)),
+        None => eprintln!("{}", message),
+    }
+}
+
+/// Mode `--json-events` : événements JSON sur stdout à la place des messages
+static JSON_EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Active ou désactive l'émission des événements JSON (`--json-events`)
+pub fn set_json_events(enabled: bool) {
+    JSON_EVENTS.store(enabled, Ordering::Relaxed);
+}
+
+/// Émet un événement (`{"type": ..., ...}` sur une ligne) en mode `--json-events`
+pub fn emit_event(event_type: &str, mut payload: serde_json::Value) {
+    if !JSON_EVENTS.load(Ordering::Relaxed) {
+        return;
+    }
+    payload["type"] = event_type.into();
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(stdout, "{}", payload);
+    let _ = stdout.flush();
+}
+
+/// Message d'état normal (masqué par `--quiet` et par la barre de progression)
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() >= 1 && !$crate::progress_active() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Avertissement non bloquant sur stderr (masqué par `--quiet`)
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() >= 1 {
+            $crate::print_warning(format_args!($($arg)*));
+        }
+    };
+}
+
+/// Détail de diagnostic (affiché seulement avec `--verbose`)
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::verbosity() >= 2 {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Données extraites d'un article Wikipedia
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WikipediaPage {
+    pub url: String,
+    pub title: String,
+    pub summary: String,
+    /// Premier paragraphe de l'article seul (phrase de définition), sans le reste du résumé
+    #[serde(default)]
+    pub lead: String,
+    pub sections: Vec<Section>,
+    pub links: Vec<String>,
+    /// Liens externes de l'article (sites officiels, sources), hors sites Wikimedia
+    #[serde(default)]
+    pub external_links: Vec<String>,
+    pub images: Vec<Image>,
+    /// Image principale (portrait ou illustration de l'infobox), distincte de `images`
+    #[serde(default)]
+    pub lead_image: Option<String>,
+    /// Lignes de l'infobox : (libellé, valeur)
+    pub infobox: Vec<(String, String)>,
+    /// Tableaux de données (`table.wikitable`) : lignes de cellules, en-têtes compris
+    #[serde(default)]
+    pub tables: Vec<Vec<Vec<String>>>,
+    pub categories: Vec<String>,
+    /// Le même article dans les autres langues : (code langue, URL de l'article)
+    #[serde(default)]
+    pub languages: Vec<(String, String)>,
+    /// Coordonnées géographiques décimales (latitude, longitude), si l'article en a
+    pub coordinates: Option<(f64, f64)>,
+    /// Sources citées (bloc `ol.references`), avec l'URL externe quand il y en a une
+    pub references: Vec<String>,
+    /// Page d'homonymie (bandeau homonymie / disambiguation détecté)
+    pub disambiguation: bool,
+    /// Articles proposés par une page d'homonymie (vide pour les autres pages)
+    pub disambiguation_options: Vec<String>,
+    /// Profondeur d'exploration (0 pour les URLs de départ, voir `--depth`)
+    pub crawl_depth: u32,
+    /// Nombre de mots du résumé et du texte des sections
+    #[serde(default)]
+    pub word_count: usize,
+    /// Temps de lecture estimé, à 200 mots par minute
+    #[serde(default)]
+    pub reading_time_minutes: usize,
+    /// Texte intégral de l'article (paragraphes, listes et titres `## …`), rempli
+    /// seulement avec `--full-text`
+    #[serde(default)]
+    pub full_text: String,
+}
+
+impl WikipediaPage {
+    /// Indique que rien d'exploitable n'a été extrait : ni titre, ni résumé, ni section
+    /// (page spéciale, page d'erreur, balisage inattendu...)
+    pub fn is_empty(&self) -> bool {
+        self.title == "Sans titre" && self.summary.is_empty() && self.sections.is_empty()
+    }
+}
+
+/// Section d'un article : titre, niveau du titre (2 pour `h2`...) et texte des paragraphes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Section {
+    pub title: String,
+    pub level: u8,
+    pub body: String,
+}
+
+/// Image d'un article : URL, légende (`figcaption` / `.thumbcaption`) et texte
+/// alternatif, vides quand la page n'en fournit pas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(from = "StoredImage")]
+pub struct Image {
+    pub url: String,
+    pub caption: String,
+    pub alt: String,
+}
+
+/// Forme sérialisée d'une image : objet complet, ou simple URL dans les
+/// `data.json` et le cache écrits par les versions précédentes
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredImage {
+    Url(String),
+    Full {
+        url: String,
+        #[serde(default)]
+        caption: String,
+        #[serde(default)]
+        alt: String,
+    },
+}
+
+impl From<StoredImage> for Image {
+    fn from(stored: StoredImage) -> Self {
+        match stored {
+            StoredImage::Url(url) => Image { url, caption: String::new(), alt: String::new() },
+            StoredImage::Full { url, caption, alt } => Image { url, caption, alt },
+        }
+    }
+}
+
+/// Schéma JSON (draft 7) des données d'un article, telles qu'écrites dans
+/// `data.json` (`--emit-schema`)
+pub fn page_schema() -> serde_json::Value {
+    serde_json::to_value(schemars::schema_for!(WikipediaPage)).expect("schéma sérialisable en JSON")
+}
+
+/// Vérifie qu'un fichier JSON produit par le scraper respecte le schéma de
+/// [`page_schema`] : un article (`data.json`), un tableau d'articles (`all.json`)
+/// ou un article par ligne (`articles.ndjson`). Renvoie le nombre d'articles lus.
+///
+/// La validation suit le schéma et non la lecture serde, plus tolérante : les
+/// images en simple URL des anciens `data.json` sont par exemple refusées.
+pub fn validate_pages_json(content: &str) -> Result<usize, ScraperError> {
+    let content = content.trim_start_matches('\u{FEFF}').trim();
+    // Un document JSON unique (objet ou tableau) ; à défaut, un document par ligne
+    let documents: Vec<(String, serde_json::Value)> = match serde_json::from_str(content) {
+        Ok(serde_json::Value::Array(pages)) => pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, page)| (format!("article {}", i + 1), page))
+            .collect(),
+        Ok(page) => vec![(String::new(), page)],
+        Err(e) => {
+            // Un document par ligne seulement si la première ligne en est un complet : un
+            // JSON indenté mal formé garde l'erreur du document entier
+            let first_is_document = content
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .is_some_and(|line| serde_json::from_str::<serde_json::Value>(line).is_ok());
+            if !first_is_document {
+                return Err(e.into());
+            }
+            content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(number, line)| {
+                    serde_json::from_str(line)
+                        .map(|page| (format!("ligne {}", number + 1), page))
+                        .map_err(|e| ScraperError::Parse(format!("ligne {} : {}", number + 1, e)))
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let validator = jsonschema::validator_for(&page_schema())
+        .map_err(|e| ScraperError::Parse(format!("schéma invalide : {}", e)))?;
+    for (position, page) in &documents {
+        if let Err(e) = validator.validate(page) {
+            let location = if position.is_empty() { String::new() } else { format!("{}, ", position) };
+            return Err(ScraperError::Parse(format!("{}{} : {}", location, e.instance_path, e)));
+        }
+    }
+    Ok(documents.len())
+}
+
+/// Nom d'hôte du Wikipedia dans la langue donnée
+pub fn wikipedia_host(lang: &str) -> String {
+    format!("{}.wikipedia.org", lang)
+}
+
+/// User-Agent par défaut : identifie l'outil et un contact, comme le demande la
+/// politique User-Agent de Wikimedia
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "wikipedia_scraper/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Jacob-dot-bit/scrappeur_wikipedia_en_rust)"
+);
+
+/// Paramètres réseau partagés par toutes les requêtes HTTP(S)
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Délai maximal pour la connexion, l'écriture et chaque lecture
+    pub timeout: Duration,
+    /// Nombre de nouvelles tentatives après un premier échec transitoire
+    pub retries: u32,
+    /// Délai avant la première nouvelle tentative (doublé ensuite)
+    pub retry_delay: Duration,
+    /// Nombre maximal de redirections suivies avant d'abandonner
+    pub max_redirects: u32,
+    /// Connexions keep-alive réutilisées d'une requête à l'autre
+    pub pool: Arc<ConnectionPool>,
+    /// Limiteur de débit par hôte, partagé par tous les threads
+    pub rate_limiter: Arc<RateLimiter>,
+    /// User-Agent envoyé avec chaque requête
+    pub user_agent: String,
+    /// Proxy HTTP par lequel passent toutes les connexions (`--proxy`)
+    pub proxy: Option<Proxy>,
+    /// Certificats d'autorités supplémentaires (`--ca-file`), acceptés en plus des
+    /// racines webpki
+    pub extra_root_certs: Vec<rustls::pki_types::CertificateDer<'static>>,
+    /// DANGEREUX : accepte n'importe quel certificat TLS (`--insecure`), pour tester
+    /// une instance locale au certificat auto-signé ; ne protège plus de rien
+    pub insecure: bool,
+}
+
+/// Mêmes valeurs que les options par défaut de la ligne de commande
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            retry_delay: Duration::from_millis(500),
+            max_redirects: 5,
+            pool: Arc::new(ConnectionPool::default()),
+            rate_limiter: Arc::new(RateLimiter::new(1.0)),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxy: None,
+            extra_root_certs: Vec::new(),
+            insecure: false,
+        }
+    }
+}
+
+/// Proxy HTTP (`http://[utilisateur:mot_de_passe@]hôte[:port]`)
+///
+/// Les requêtes HTTPS passent par un tunnel `CONNECT hôte:port` ouvert sur le
+/// proxy ; les requêtes HTTP lui sont envoyées avec l'URL complète.
+#[derive(Clone, PartialEq)]
+pub struct Proxy {
+    pub host: String,
+    pub port: u16,
+    /// Valeur du header `Proxy-Authorization`, si l'URL contient des identifiants
+    authorization: Option<String>,
+}
+
+impl Proxy {
+    /// Analyse l'URL d'un proxy ; sans port explicite, le port 80 est utilisé
+    pub fn parse(url: &str) -> Result<Proxy, ScraperError> {
+        let url = url.trim();
+        if url.starts_with("https://") {
+            return Err(ScraperError::Parse(format!(
+                "Proxy \"{}\" : seuls les proxys http:// sont pris en charge",
+                url
+            )));
+        }
+        let rest = url.strip_prefix("http://").unwrap_or(url);
+        let authority = rest.split('/').next().unwrap_or_default();
+
+        // Les identifiants sont séparés de l'hôte par le dernier `@`
+        let (credentials, host_port) = match authority.rsplit_once('@') {
+            Some((credentials, host_port)) => (Some(credentials), host_port),
+            None => (None, authority),
+        };
+        let parts = parse_url(&format!("http://{}", host_port))?;
+        let authorization = credentials.map(|credentials| {
+            let (user, password) = credentials.split_once(':').unwrap_or((credentials, ""));
+            let pair = format!("{}:{}", percent_decode(user), percent_decode(password));
+            format!("Basic {}", base64_encode(pair.as_bytes()))
+        });
+
+        Ok(Proxy {
+            host: parts.host,
+            port: parts.port,
+            authorization,
+        })
+    }
+}
+
+/// N'affiche jamais les identifiants du proxy
+impl std::fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Proxy")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("authentifié", &self.authorization.is_some())
+            .finish()
+    }
+}
+
+/// Encode des octets en Base64 standard (avec `=` de remplissage)
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Limiteur de débit par hôte (seau à jetons d'une capacité d'un jeton)
+///
+/// Chaque requête consomme un jeton, regénéré au rythme de `rate` par seconde :
+/// deux requêtes vers un même hôte sont donc espacées d'au moins `1 / rate`
+/// secondes, quel que soit le nombre de threads. Le débit par défaut (1.0)
+/// correspond à la pause historique d'une seconde entre deux requêtes.
+pub struct RateLimiter {
+    interval: Duration,
+    /// Instant à partir duquel le prochain jeton de chaque hôte est disponible
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> RateLimiter {
+        RateLimiter::with_interval(Duration::from_secs_f64(1.0 / requests_per_second))
+    }
+
+    /// Limiteur défini par le délai minimal entre deux requêtes vers un même hôte ;
+    /// un délai nul désactive la limitation
+    ///
+    /// Le délai est plafonné à un siècle (une attente sans fin en pratique) pour que
+    /// le calcul du prochain créneau ne déborde pas d'un `Instant`.
+    pub fn with_interval(interval: Duration) -> RateLimiter {
+        const MAX_INTERVAL: Duration = Duration::from_secs(100 * 365 * 24 * 3600);
+        RateLimiter {
+            interval: interval.min(MAX_INTERVAL),
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Attend qu'un jeton soit disponible pour l'hôte et le consomme
+    fn acquire(&self, host: &str) {
+        let wait_until = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = next_slot.get(host).copied().unwrap_or(now).max(now);
+            next_slot.insert(host.to_string(), slot + self.interval);
+            slot
+        };
+
+        let now = Instant::now();
+        if wait_until > now {
+            std::thread::sleep(wait_until - now);
+        }
+    }
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.interval.is_zero() {
+            write!(f, "RateLimiter(illimité)")
+        } else {
+            write!(f, "RateLimiter({:.2} req/s)", 1.0 / self.interval.as_secs_f64())
+        }
+    }
+}
+
+/// Schéma d'une URL, qui détermine le transport utilisé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    Http,
+    Https,
+}
+
+impl Scheme {
+    fn default_port(self) -> u16 {
+        match self {
+            Scheme::Http => 80,
+            Scheme::Https => 443,
+        }
+    }
+}
+
+/// Composants d'une URL utiles pour ouvrir la connexion
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlParts {
+    pub scheme: Scheme,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+/// Reconstruit l'URL complète (le port n'apparaît que s'il n'est pas celui par défaut)
+impl std::fmt::Display for UrlParts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = match self.scheme {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        };
+        if self.port == self.scheme.default_port() {
+            write!(f, "{}://{}{}", scheme, self.host, self.path)
+        } else {
+            write!(f, "{}://{}:{}{}", scheme, self.host, self.port, self.path)
+        }
+    }
+}
+
+/// Ligne de statut d'une réponse HTTP (`HTTP/1.1 301 Moved Permanently`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusLine {
+    version: String,
+    code: u16,
+    reason: String,
+}
+
+impl StatusLine {
+    /// Découpe la ligne en version, code numérique et raison (éventuellement vide,
+    /// comme dans `HTTP/2 200`)
+    fn parse(line: &str) -> Result<StatusLine, ScraperError> {
+        let invalid = || ScraperError::Parse(format!("Ligne de statut HTTP invalide : \"{}\"", line.trim()));
+
+        let mut parts = line.trim().splitn(3, ' ');
+        let version = parts.next().filter(|v| v.starts_with("HTTP/")).ok_or_else(invalid)?;
+        let code = parts
+            .next()
+            .and_then(|c| c.parse::<u16>().ok())
+            .filter(|c| (100..600).contains(c))
+            .ok_or_else(invalid)?;
+        let reason = parts.next().unwrap_or("").trim();
+
+        Ok(StatusLine {
+            version: version.to_string(),
+            code,
+            reason: reason.to_string(),
+        })
+    }
+
+    fn is_success(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+
+    /// Redirections suivies automatiquement
+    fn is_redirect(&self) -> bool {
+        matches!(self.code, 301 | 302 | 303 | 307 | 308)
+    }
+
+    /// Réponse sans body par définition (informative, 204 No Content, 304 Not Modified)
+    fn has_no_body(&self) -> bool {
+        (100..200).contains(&self.code) || self.code == 204 || self.code == 304
+    }
+}
+
+impl std::fmt::Display for StatusLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.reason.is_empty() {
+            write!(f, "{} {}", self.version, self.code)
+        } else {
+            write!(f, "{} {} {}", self.version, self.code, self.reason)
+        }
+    }
+}
+
+/// Réponse HTTP brute : headers (ligne de statut incluse) et body déjà désencapsulé
+/// du transfert chunked
+#[derive(Debug)]
+struct HttpResponse {
+    headers: String,
+    body: Vec<u8>,
+    /// La connexion peut être réutilisée pour une requête suivante
+    keep_alive: bool,
+}
+
+/// Validateurs de cache HTTP d'une page (`ETag`, `Last-Modified`), renvoyés
+/// tels quels dans une requête conditionnelle
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &str) -> Self {
+        Validators {
+            etag: extract_header(headers, "ETag"),
+            last_modified: extract_header(headers, "Last-Modified"),
+        }
+    }
+}
+
+/// Body d'une réponse réussie, décompressé mais pas encore converti en texte
+#[derive(Debug)]
+struct FetchedBody {
+    bytes: Vec<u8>,
+    /// Charset annoncé par le header Content-Type, le cas échéant
+    charset: Option<String>,
+    /// URL atteinte après les éventuelles redirections
+    final_url: UrlParts,
+    /// Validateurs de cache annoncés par le serveur
+    validators: Validators,
+    /// Le serveur a répondu 304 : le body est vide, la copie en cache est à jour
+    not_modified: bool,
+}
+
+impl FetchedBody {
+    /// Convertit le body en texte selon le charset du header Content-Type, ou à
+    /// défaut celui déclaré dans une balise `<meta charset>` du document
+    fn into_text(self) -> String {
+        let charset = self.charset.clone().or_else(|| sniff_meta_charset(&self.bytes));
+        bytes_to_string(self.bytes, charset.as_deref())
+    }
+}
+
+/// Cherche un charset déclaré dans les premiers octets d'un document HTML
+/// (`<meta charset="...">` ou `<meta http-equiv="Content-Type" content="...; charset=...">`)
+fn sniff_meta_charset(bytes: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_lowercase();
+    let start = head.find("charset=")? + "charset=".len();
+    let charset: String = head[start..]
+        .trim_start_matches(['"', '\''])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect();
+
+    if charset.is_empty() {
+        None
+    } else {
+        Some(charset)
+    }
+}
+
+/// Flux de transport vers un serveur : TCP brut ou TLS
+enum Transport {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Transport::Plain(sock) => sock.read(buf),
+            Transport::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Transport::Plain(sock) => sock.write(buf),
+            Transport::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Transport::Plain(sock) => sock.flush(),
+            Transport::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Connexions persistantes (keep-alive), au plus une par schéma/hôte/port
+///
+/// Évite de refaire une poignée de main TLS pour chaque page d'un même hôte.
+#[derive(Default)]
+pub struct ConnectionPool {
+    connections: Mutex<HashMap<(Scheme, String, u16), Transport>>,
+}
+
+impl ConnectionPool {
+    /// Retire du pool la connexion ouverte vers la cible, s'il y en a une
+    fn take(&self, target: &UrlParts) -> Option<Transport> {
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        connections.remove(&(target.scheme, target.host.clone(), target.port))
+    }
+
+    /// Remet une connexion dans le pool pour une prochaine requête
+    fn put(&self, target: &UrlParts, transport: Transport) {
+        let mut connections = self.connections.lock().unwrap_or_else(|e| e.into_inner());
+        connections.insert((target.scheme, target.host.clone(), target.port), transport);
+    }
+}
+
+impl std::fmt::Debug for ConnectionPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let count = self.connections.lock().map(|c| c.len()).unwrap_or(0);
+        write!(f, "ConnectionPool({} connexion(s))", count)
+    }
+}
+
+/// Erreurs renvoyées par le scraper, par nature d'échec
+#[derive(Debug, thiserror::Error)]
+pub enum ScraperError {
+    /// Connexion impossible ou délai d'attente dépassé
+    #[error("{0}")]
+    Connection(String),
+    /// Échec de la mise en place de TLS
+    #[error("Erreur TLS: {0}")]
+    Tls(String),
+    /// Réponse HTTP hors 2xx (`status` contient la ligne de statut complète et
+    /// `body` le début du texte de la réponse, souvent l'explication du serveur)
+    ///
+    /// `retry_after` reprend le header `Retry-After` des réponses 429 et 503.
+    #[error("Erreur HTTP: {status}{}", if body.is_empty() { String::new() } else { format!(" — {}", body) })]
+    HttpStatus {
+        code: u16,
+        status: String,
+        body: String,
+        retry_after: Option<Duration>,
+    },
+    /// Redirection invalide ou trop nombreuses
+    #[error("Erreur HTTP: {0}")]
+    Redirect(String),
+    /// Réponse, URL ou document impossible à interpréter
+    #[error("{0}")]
+    Parse(String),
+    /// Page sans contenu d'article exploitable (page spéciale, page d'erreur...)
+    #[error("Aucun contenu d'article trouvé sur {0}")]
+    EmptyPage(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Erreur YAML: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("Erreur SQLite: {0}")]
+    Database(#[from] rusqlite::Error),
+}
+
+impl ScraperError {
+    /// Indique si une nouvelle tentative a des chances de réussir : erreurs
+    /// réseau, 5xx et 429 (trop de requêtes) oui, autres erreurs HTTP 4xx ou de parsing non
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ScraperError::Connection(_) | ScraperError::Io(_) => true,
+            ScraperError::HttpStatus { code, .. } => *code >= 500 || *code == 429,
+            _ => false,
+        }
+    }
+}
+
+/// Fonction pour rechercher des articles sur Wikipedia par mot-clé
+///
+/// L'article dont le titre est exactement le mot-clé, s'il existe, est placé en tête
+/// des résultats ; avec `exact`, c'est le seul résultat possible et la recherche
+/// plein texte n'est pas lancée. Les liens vers d'autres espaces de noms ne sont
+/// gardés que s'ils figurent dans `include_namespaces`.
+pub fn search_wikipedia(
+    mot_cle: &str,
+    max_resultats: usize,
+    lang: &str,
+    use_api: bool,
+    exact: bool,
+    include_namespaces: &[String],
+    config: &HttpConfig,
+) -> Result<Vec<String>, ScraperError> {
+    let host = wikipedia_host(lang);
+    emit_event("search_started", serde_json::json!({ "keyword": mot_cle, "lang": lang }));
+
+    // URL directe (fallback)
+    let direct_url = format!("https://{}/wiki/{}", host, encode_wiki_title(mot_cle));
+
+    let mut results: Vec<String> = Vec::new();
+
+    // Titre exact : une seule requête suffit quand on connaît déjà l'article
+    match resolve_exact_title(mot_cle, &host, config) {
+        Ok(Some(url)) => {
+            info!("  ✓ Article au titre exact trouvé : {}", url);
+            results.push(url);
+        }
+        Ok(None) => info!("  Aucun article intitulé exactement \"{}\"", mot_cle),
+        Err(e) => warn!("  ⚠ Résolution du titre exact impossible ({})", e),
+    }
+
+    if exact {
+        for (rank, url) in results.iter().enumerate() {
+            emit_event("result_found", serde_json::json!({ "rank": rank + 1, "url": url }));
+        }
+        return Ok(results);
+    }
+
+    let mut api_succeeded = results.len() >= max_resultats;
+
+    // Recherche via l'API MediaWiki (JSON), plus fiable que le HTML de Special:Search
+    if use_api && !api_succeeded {
+        match search_via_api(mot_cle, max_resultats, &host, config) {
+            Ok(api_results) => {
+                results.extend(api_results);
+                api_succeeded = true;
+            }
+            Err(e) => warn!("  ⚠ API de recherche indisponible ({}), repli sur la page de recherche HTML", e),
+        }
+    }
+
+    if !api_succeeded {
+        // Récupérer la page de recherche HTML
+        info!("  Récupération de la page de recherche https://{}/w/index.php?search={}", host, mot_cle);
+        // Forcer l'affichage de la page Special:Search pour obtenir la liste de résultats
+        let search_path_html = format!("/w/index.php?search={}&title=Special%3ASearch&fulltext=1", encode_query(mot_cle));
+
+        if let Ok(html_content) = https_get(&host, 443, &search_path_html, config) {
+            let document = Html::parse_document(&html_content);
+
+            // Extraire uniquement les liens listés dans la page de recherche
+            // Priorité aux éléments standard de la recherche :
+            // - `div.mw-search-result-heading a` (nouveau markup)
+            // - `div.mw-search-results li a` (fallback historique)
+            let selectors = [
+                "div.mw-search-result-heading a",
+                "div.mw-search-results li a",
+                "ul.mw-search-results li a",
+            ];
+
+            for sel in selectors.iter() {
+                if results.len() >= max_resultats { break; }
+                if let Ok(s) = Selector::parse(sel) {
+                    debug!("  · Sélecteur `{}` : {} élément(s)", sel, document.select(&s).count());
+                    for el in document.select(&s) {
+                        if results.len() >= max_resultats { break; }
+                        if let Some(href) = el.value().attr("href") {
+                            if href.starts_with("/wiki/") && is_followed_wiki_link(href, include_namespaces) {
+                                let url = format!("https://{}{}", host, href);
+                                if !results.contains(&url) {
+                                    results.push(url);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Si rien trouvé, fallback sur l'URL directe
+    if results.is_empty() {
+        results.push(direct_url);
+    }
+
+    // Dédupliquer (case-insensitive) tout en préservant l'ordre et tronquer à max_resultats
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut unique_results: Vec<String> = Vec::new();
+    for u in results.into_iter() {
+        let mut key = u.to_lowercase();
+        if key.ends_with('/') { key = key.trim_end_matches('/').to_string(); }
+        if !seen.contains(&key) {
+            seen.insert(key);
+            unique_results.push(u);
+        }
+        if unique_results.len() >= max_resultats { break; }
+    }
+
+    for (rank, url) in unique_results.iter().enumerate() {
+        emit_event("result_found", serde_json::json!({ "rank": rank + 1, "url": url }));
+    }
+
+    Ok(unique_results)
+}
+
+/// Cherche l'article dont le titre est exactement `mot_cle` via l'API REST
+/// (`/api/rest_v1/page/summary/<titre>`), qui suit les redirections de titre
+///
+/// Renvoie l'URL `/wiki/Titre` canonique, ou `None` si aucun article ne porte ce titre.
+fn resolve_exact_title(mot_cle: &str, host: &str, config: &HttpConfig) -> Result<Option<String>, ScraperError> {
+    let api_path = format!("/api/rest_v1/page/summary/{}", encode_wiki_title(mot_cle.trim()));
+    info!("  Recherche du titre exact https://{}{}", host, api_path);
+
+    let json = match https_get(host, 443, &api_path, config) {
+        Ok(json) => json,
+        Err(ScraperError::HttpStatus { code: 404, .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let response: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| ScraperError::Parse(format!("Réponse JSON de l'API REST invalide: {}", e)))?;
+
+    // Articles et pages d'homonymie uniquement (pas la page d'accueil ni les pages spéciales)
+    if !matches!(response["type"].as_str(), Some("standard" | "disambiguation")) {
+        return Ok(None);
+    }
+    Ok(response["titles"]["canonical"]
+        .as_str()
+        .or_else(|| response["title"].as_str())
+        .map(|title| format!("https://{}/wiki/{}", host, encode_wiki_title(title))))
+}
+
+/// Recherche via l'API MediaWiki (`action=query&list=search`) et convertit
+/// chaque titre trouvé en URL `/wiki/Titre`
+fn search_via_api(
+    mot_cle: &str,
+    max_resultats: usize,
+    host: &str,
+    config: &HttpConfig,
+) -> Result<Vec<String>, ScraperError> {
+    let api_path = format!(
+        "/w/api.php?action=query&list=search&srsearch={}&srlimit={}&format=json&utf8=1",
+        encode_query(mot_cle),
+        max_resultats
+    );
+    info!("  Interrogation de l'API de recherche https://{}{}", host, api_path);
+
+    let json = https_get(host, 443, &api_path, config)?;
+    let response: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| ScraperError::Parse(format!("Réponse JSON de l'API invalide: {}", e)))?;
+
+    let search_results = response["query"]["search"]
+        .as_array()
+        .ok_or_else(|| ScraperError::Parse("Réponse de l'API sans liste de résultats".to_string()))?;
+
+    Ok(search_results
+        .iter()
+        .filter_map(|result| result["title"].as_str())
+        .map(|title| format!("https://{}/wiki/{}", host, encode_wiki_title(title)))
+        .collect())
+}
+
+/// Décode les séquences `%XX` d'une chaîne (les séquences invalides sont conservées)
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap());
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Forme canonique d'une URL pour la déduplication : sans fragment ni query
+/// string, chemin décodé et sans `/` final
+pub fn canonical_url(url: &str) -> String {
+    let url = url.split(['#', '?']).next().unwrap_or(url);
+    percent_decode(url).trim_end_matches('/').to_string()
+}
+
+/// Forme canonique d'un chemin `/wiki/...` : sans query string et décodé
+fn canonical_wiki_path(href: &str) -> String {
+    let path = href.split('?').next().unwrap_or(href);
+    percent_decode(path)
+}
+
+/// Indique si un lien `/wiki/...` est à garder : pas d'ancre, et pas d'espace de noms
+/// (`Catégorie:`, `Fichier:`, `Portail:`…) sauf ceux de `include_namespaces`,
+/// comparés sans tenir compte de la casse ni de `_` / espace
+fn is_followed_wiki_link(href: &str, include_namespaces: &[String]) -> bool {
+    if href.contains('#') {
+        return false;
+    }
+    let Some((namespace, _)) = href.split_once(':') else {
+        return true;
+    };
+    let normalize = |name: &str| name.trim().replace('_', " ").to_lowercase();
+    let namespace = normalize(&percent_decode(namespace.trim_start_matches("/wiki/")));
+    include_namespaces.iter().any(|allowed| normalize(allowed) == namespace)
+}
+
+/// Encode une chaîne en pourcentage, octet UTF-8 par octet UTF-8 ;
+/// `space` remplace l'espace (`_` pour les chemins `/wiki/`, `+` pour les requêtes)
+fn percent_encode(s: &str, space: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            b' ' => encoded.push_str(space),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Encode un titre d'article pour un chemin `/wiki/Titre` (espaces → `_`)
+pub fn encode_wiki_title(title: &str) -> String {
+    percent_encode(title, "_")
+}
+
+/// Encode une valeur de paramètre de requête (espaces → `+`)
+pub fn encode_query(value: &str) -> String {
+    percent_encode(value, "+")
+}
+
+/// Taille des images d'article retenue dans les URLs extraites
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSize {
+    /// Miniature utilisée par la page, telle quelle
+    AsPage,
+    /// Miniature de cette largeur en pixels (`--image-width`)
+    Width(u32),
+    /// Fichier original en pleine résolution (`--original-images`)
+    Original,
+}
+
+/// Limites et options d'extraction d'un article
+#[derive(Debug, Clone)]
+pub struct ScrapeOptions {
+    /// Nombre maximum de liens conservés (0 = pas de limite)
+    pub max_links: usize,
+    /// Nombre maximum d'images conservées (0 = pas de limite)
+    pub max_images: usize,
+    /// Largeur et hauteur minimales (en pixels) d'une image conservée
+    pub min_image_size: u32,
+    pub image_size: ImageSize,
+    /// Résumé vide : le demander à l'API REST (`/api/rest_v1/page/summary/<titre>`)
+    pub api_fallback: bool,
+    /// Espaces de noms (`Catégorie`, `Portail`…) dont les liens sont gardés ;
+    /// vide = uniquement les articles
+    pub include_namespaces: Vec<String>,
+    /// Extraire aussi le texte intégral de l'article (`full_text`)
+    pub full_text: bool,
+}
+
+/// Mêmes valeurs que les options par défaut de la ligne de commande
+impl Default for ScrapeOptions {
+    fn default() -> Self {
+        ScrapeOptions {
+            max_links: 500,
+            max_images: 20,
+            min_image_size: 100,
+            image_size: ImageSize::AsPage,
+            api_fallback: false,
+            include_namespaces: Vec::new(),
+            full_text: false,
+        }
+    }
+}
+
+/// Fonction pour scraper une page Wikipedia
+///
+/// Télécharge la page puis délègue l'extraction à [`parse_wikipedia_html`].
+pub fn scrape_wikipedia(
+    url: &str,
+    mot_cle: Option<&str>,
+    lang: &str,
+    options: &ScrapeOptions,
+    config: &HttpConfig,
+) -> Result<WikipediaPage, ScraperError> {
+    match scrape_wikipedia_if_modified(url, None, mot_cle, lang, options, config)? {
+        Some((page, _)) => Ok(page),
+        None => Err(ScraperError::Parse(format!(
+            "Réponse 304 inattendue pour {} (aucune requête conditionnelle envoyée)",
+            url
+        ))),
+    }
+}
+
+/// Page téléchargée telle que reçue : HTML brut, URL finale (après redirections)
+/// et validateurs HTTP, de quoi la ré-analyser plus tard avec d'autres options
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    pub html: String,
+    pub final_url: String,
+    pub validators: Validators,
+}
+
+/// Scrape une page Wikipedia par une requête conditionnelle
+///
+/// Avec des `validators` issus d'un scraping précédent, le serveur peut répondre
+/// 304 Not Modified : la fonction renvoie alors `None` et la copie locale reste
+/// valable (voir [`page_from_html`] pour l'analyser). Sinon la page est renvoyée
+/// avec le HTML téléchargé et ses nouveaux validateurs.
+pub fn scrape_wikipedia_if_modified(
+    url: &str,
+    validators: Option<&Validators>,
+    mot_cle: Option<&str>,
+    lang: &str,
+    options: &ScrapeOptions,
+    config: &HttpConfig,
+) -> Result<Option<(WikipediaPage, FetchedPage)>, ScraperError> {
+    let url_parts = parse_url(url)?;
+
+    let fetched = http_get_conditional(&url_parts, validators, config)?;
+    if fetched.not_modified {
+        return Ok(None);
+    }
+
+    // URL finale après redirections : c'est elle qui identifie l'article
+    let fetched = FetchedPage {
+        final_url: fetched.final_url.to_string(),
+        validators: fetched.validators.clone(),
+        html: fetched.into_text(),
+    };
+    let page = page_from_html(&fetched.html, &fetched.final_url, mot_cle, lang, options, config)?;
+    Ok(Some((page, fetched)))
+}
+
+/// Analyse le HTML d'un article téléchargé avec les options données, complète
+/// le résumé par l'API REST si `api_fallback` le demande, et refuse les pages
+/// sans contenu d'article
+pub fn page_from_html(
+    html: &str,
+    final_url: &str,
+    mot_cle: Option<&str>,
+    lang: &str,
+    options: &ScrapeOptions,
+    config: &HttpConfig,
+) -> Result<WikipediaPage, ScraperError> {
+    let mut page = parse_wikipedia_html(html, final_url, mot_cle, lang, options);
+    if page.summary.is_empty() && options.api_fallback {
+        match fetch_rest_summary(&parse_url(final_url)?, config) {
+            Ok(Some(extract)) => {
+                info!("  ↪ Résumé récupéré via l'API REST");
+                page.lead = extract.split('\n').next().unwrap_or_default().to_string();
+                page.summary = extract;
+                page.word_count = count_words(&page.summary, &page.sections);
+                page.reading_time_minutes = page.word_count.div_ceil(WORDS_PER_MINUTE);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("  ⚠ API REST de résumé indisponible ({})", e),
+        }
+    }
+    // Ni titre, ni résumé, ni section : inutile de sauvegarder des fichiers vides
+    if page.is_empty() {
+        return Err(ScraperError::EmptyPage(page.url));
+    }
+    Ok(page)
+}
+
+/// Demande le résumé d'un article à l'API REST (`/api/rest_v1/page/summary/<titre>`)
+/// du même hôte, en partant de l'URL `/wiki/<titre>` de l'article
+///
+/// Renvoie `None` si l'URL n'est pas celle d'un article ou si l'API n'a pas d'extrait.
+fn fetch_rest_summary(article_url: &UrlParts, config: &HttpConfig) -> Result<Option<String>, ScraperError> {
+    let Some(title) = article_url.path.strip_prefix("/wiki/") else {
+        return Ok(None);
+    };
+    let title = title.split(['?', '#']).next().unwrap_or_default();
+    if title.is_empty() {
+        return Ok(None);
+    }
+    let api_url = UrlParts {
+        path: format!("/api/rest_v1/page/summary/{}", title),
+        ..article_url.clone()
+    };
+    info!("  Interrogation de l'API REST {}", api_url);
+
+    let (json, _) = http_get(&api_url, config)?;
+    let response: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| ScraperError::Parse(format!("Réponse JSON de l'API REST invalide: {}", e)))?;
+    Ok(response["extract"]
+        .as_str()
+        .map(|extract| extract.trim().to_string())
+        .filter(|extract| !extract.is_empty()))
+}
+
+/// Extrait toutes les données d'un article à partir de son HTML, sans aucun accès réseau
+///
+/// `url` est l'adresse finale de la page (après redirections), reprise telle quelle
+/// dans le champ `url`.
+pub fn parse_wikipedia_html(
+    html_content: &str,
+    url: &str,
+    mot_cle: Option<&str>,
+    lang: &str,
+    options: &ScrapeOptions,
+) -> WikipediaPage {
+    let host = wikipedia_host(lang);
+    let document = Html::parse_document(html_content);
+
+    // Extraire le titre
+    let title_selector = Selector::parse("h1#firstHeading, h1.firstHeading").unwrap();
+    let title = document
+        .select(&title_selector)
+        .next()
+        .map(|el| decode_entities(&el.text().collect::<String>()))
+        .unwrap_or_else(|| "Sans titre".to_string());
+
+    // Extraire le résumé avec fallbacks
+    let (summary, lead) = extract_summary(&document);
+    let summary = decode_entities(&summary);
+    let lead = decode_entities(&lead);
+
+    // Extraire les sections (titres et contenu), sans répéter l'introduction
+    let mut sections = extract_sections(&document);
+    drop_summary_overlap(&summary, &mut sections);
+
+    // Extraire les liens internes
+         // Extraire les liens internes (filtrés par mot-clé si fourni)
+        let link_selector = Selector::parse("#mw-content-text a[href^='/wiki/']").unwrap();
+    let keyword_lower_opt = mot_cle.map(|k| k.to_lowercase());
+    let keyword_url_opt = mot_cle.map(|k| k.to_lowercase().replace(' ', "_"));
+
+    let mut links: Vec<String> = document
+        .select(&link_selector)
+        .filter_map(|el: ElementRef| {
+            let href = el.value().attr("href")?;
+            // Ignorer les ancrages et les liens hors articles (sauf espaces de noms demandés)
+            if !is_followed_wiki_link(href, &options.include_namespaces) {
+                return None;
+            }
+            // Retirer la query string et décoder le chemin pour des URLs lisibles
+            let href = canonical_wiki_path(href);
+
+            // Si mot-clé fourni, vérifier plusieurs endroits (texte du lien, title, URL)
+            if let Some(ref kw) = keyword_lower_opt {
+                let text = el.text().collect::<String>().to_lowercase();
+                let title_attr = el.value().attr("title").unwrap_or("").to_lowercase();
+                let href_lower = href.to_lowercase();
+                let kw_url = keyword_url_opt.as_deref().unwrap_or("");
+
+                let contains = text.contains(kw)
+                    || title_attr.contains(kw)
+                    || href_lower.contains(kw)
+                    || (!kw_url.is_empty() && href_lower.contains(kw_url));
+
+                // Si le lien lui-même ne contient pas le mot-clé, vérifier le paragraphe ancêtre
+                if !contains {
+                    let parent_p_opt = el.ancestors().find_map(|node| {
+                        if let Some(elem) = ElementRef::wrap(node) {
+                            // comparer le nom local de la balise (ex: "p")
+                            if elem.value().name.local.as_ref() == "p" {
+                                return Some(elem);
+                            }
+                        }
+                        None
+                    });
+
+                    if let Some(parent_p) = parent_p_opt {
+                        let parent_text = parent_p.text().collect::<String>().to_lowercase();
+                        if parent_text.contains(kw) {
+                            return Some(format!("https://{}{}", host, href));
+                        }
+                    }
+
+                    return None;
+                }
+            }
+
+            Some(format!("https://{}{}", host, href))
+        })
+        .collect();
+
+    // Dédupliquer sans tenir compte de la casse, en gardant le premier ordre d'apparition
+    let mut seen_links = HashSet::new();
+    links.retain(|link| seen_links.insert(link.to_lowercase()));
+ 
+
+
+    // Extraire les images (filtrer les icônes)
+    let image_selector = Selector::parse("img[src]").unwrap();
+    let mut images: Vec<Image> = document
+        .select(&image_selector)
+        .filter_map(|el| {
+            let url = article_image_url(&el, options.min_image_size)?;
+            Some(Image {
+                url: resize_image_url(&url, options.image_size),
+                caption: image_caption(&el),
+                alt: collapse_whitespace(el.value().attr("alt").unwrap_or_default()),
+            })
+        })
+        .collect();
+    let lead_image = extract_lead_image(&document, options.min_image_size).map(|url| resize_image_url(&url, options.image_size));
+    debug!(
+        "  · Extraits : {} section(s), {} lien(s), {} image(s) avant limites",
+        sections.len(),
+        links.len(),
+        images.len()
+    );
+    truncate_to_limit(&mut links, options.max_links);
+    truncate_to_limit(&mut images, options.max_images);
+
+    let external_links = extract_external_links(&document);
+    let languages = extract_languages(&document);
+    let infobox = extract_infobox(&document);
+    let tables = extract_tables(&document);
+    let coordinates = extract_coordinates(&document);
+    let references = extract_references(&document);
+    let disambiguation = is_disambiguation_page(&document);
+    let disambiguation_options = if disambiguation {
+        extract_disambiguation_options(&document, &host)
+    } else {
+        Vec::new()
+    };
+
+    // Extraire les catégories visibles (le bloc des catégories cachées est ignoré,
+    // tout comme le libellé « Catégories : » placé hors de la liste)
+    let category_selector = Selector::parse("#mw-normal-catlinks ul li a").unwrap();
+    let categories: Vec<String> = document
+        .select(&category_selector)
+        .map(|el| decode_entities(&el.text().collect::<String>()))
+        .filter(|category| !category.is_empty())
+        .collect();
+
+    let word_count = count_words(&summary, &sections);
+    let full_text = if options.full_text { extract_full_text(&document) } else { String::new() };
+
+    WikipediaPage {
+        url: url.to_string(),
+        title,
+        summary,
+        lead,
+        sections,
+        links,
+        external_links,
+        images,
+        lead_image,
+        infobox,
+        tables,
+        categories,
+        languages,
+        coordinates,
+        references,
+        disambiguation,
+        disambiguation_options,
+        crawl_depth: 0,
+        word_count,
+        reading_time_minutes: word_count.div_ceil(WORDS_PER_MINUTE),
+        full_text,
+    }
+}
+
+/// Sections d'annexe retirées par `--skip-appendix-sections` quand aucune liste n'est donnée
+pub const DEFAULT_APPENDIX_SECTIONS: [&str; 13] = [
+    "Notes et références",
+    "Notes",
+    "Références",
+    "Liens externes",
+    "Bibliographie",
+    "Voir aussi",
+    "Articles connexes",
+    "Annexes",
+    "References",
+    "External links",
+    "See also",
+    "Further reading",
+    "Bibliography",
+];
+
+/// Retire les sections dont le titre figure dans `titles` (sans tenir compte de
+/// la casse ni des accents) et met à jour le nombre de mots
+pub fn remove_appendix_sections(page: &mut WikipediaPage, titles: &[String]) {
+    let folded: HashSet<String> = titles.iter().map(|title| fold_title(title)).collect();
+    page.sections.retain(|section| !folded.contains(&fold_title(&section.title)));
+    page.word_count = count_words(&page.summary, &page.sections);
+    page.reading_time_minutes = page.word_count.div_ceil(WORDS_PER_MINUTE);
+}
+
+/// Section de l'article dont le titre est `name` (sans tenir compte de la casse
+/// ni des accents) ; la première si plusieurs sections portent ce titre
+pub fn find_section<'a>(page: &'a WikipediaPage, name: &str) -> Option<&'a Section> {
+    let folded = fold_title(name);
+    page.sections.iter().find(|section| fold_title(&section.title) == folded)
+}
+
+/// Forme de comparaison d'un titre : minuscules, sans accents, espaces normalisés
+fn fold_title(title: &str) -> String {
+    collapse_whitespace(title)
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'à' | 'â' | 'ä' | 'á' | 'ã' => "a".to_string(),
+            'é' | 'è' | 'ê' | 'ë' => "e".to_string(),
+            'î' | 'ï' | 'í' | 'ì' => "i".to_string(),
+            'ô' | 'ö' | 'ó' | 'ò' | 'õ' => "o".to_string(),
+            'ù' | 'û' | 'ü' | 'ú' => "u".to_string(),
+            'ç' => "c".to_string(),
+            'ÿ' => "y".to_string(),
+            'ñ' => "n".to_string(),
+            'œ' => "oe".to_string(),
+            'æ' => "ae".to_string(),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+/// Vitesse de lecture retenue pour `reading_time_minutes`
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Compte les mots du résumé et du texte de chaque section
+fn count_words(summary: &str, sections: &[Section]) -> usize {
+    summary.split_whitespace().count()
+        + sections
+            .iter()
+            .map(|section| section.body.split_whitespace().count())
+            .sum::<usize>()
+}
+
+/// Extrait les sections de l'article avec le texte des paragraphes qui les suivent
+///
+/// Le niveau est donné par la balise de titre englobant le `.mw-headline`, et le
+/// contenu s'arrête au titre suivant, quel que soit son niveau.
+fn extract_sections(document: &Html) -> Vec<Section> {
+    let headline_selector = Selector::parse(".mw-headline").unwrap();
+    let mut sections = Vec::new();
+
+    for headline in document.select(&headline_selector) {
+        let title = decode_entities(&headline.text().collect::<String>());
+        if title.is_empty() || title.len() <= 1 {
+            continue;
+        }
+        // Les titres des boîtes de navigation et des listes de références ne sont pas des sections
+        let in_noise_block = headline.ancestors().filter_map(ElementRef::wrap).any(|el| {
+            el.value().classes().any(|c| c == "navbox" || c == "reflist")
+        });
+        if in_noise_block {
+            continue;
+        }
+
+        let heading = headline
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|el| heading_level(el).is_some());
+        let Some(heading) = heading else {
+            sections.push(Section { title, level: 2, body: String::new() });
+            continue;
+        };
+        let level = heading_level(&heading).unwrap_or(2);
+
+        // Avec le balisage récent de MediaWiki, le titre est enveloppé dans un div.mw-heading
+        let block = match heading.parent().and_then(ElementRef::wrap) {
+            Some(parent) if parent.value().classes().any(|c| c == "mw-heading") => parent,
+            _ => heading,
+        };
+
+        let mut paragraphs: Vec<String> = Vec::new();
+        for sibling in block.next_siblings().filter_map(ElementRef::wrap) {
+            if is_heading_block(&sibling) {
+                break;
+            }
+            if sibling.value().name() == "p" {
+                let text = decode_entities(&prose_text(&sibling));
+                if !text.is_empty() {
+                    paragraphs.push(text);
+                }
+            }
+        }
+
+        sections.push(Section {
+            title,
+            level,
+            body: paragraphs.join("\n\n"),
+        });
+    }
+
+    sections
+}
+
+/// Retire du texte de la première section les paragraphes identiques (espaces
+/// normalisés) à un paragraphe du résumé : c'est le cas quand un sous-titre précède
+/// l'introduction, dont les paragraphes sont alors repris par les deux
+fn drop_summary_overlap(summary: &str, sections: &mut [Section]) {
+    let Some(first) = sections.first_mut() else {
+        return;
+    };
+    let summary_paragraphs: HashSet<String> = summary
+        .split("\n\n")
+        .map(collapse_whitespace)
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect();
+    if summary_paragraphs.is_empty() {
+        return;
+    }
+    first.body = first
+        .body
+        .split("\n\n")
+        .filter(|paragraph| !summary_paragraphs.contains(&collapse_whitespace(paragraph)))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+}
+
+/// Niveau d'une balise de titre (`h2` → 2), `None` pour les autres éléments
+fn heading_level(element: &ElementRef) -> Option<u8> {
+    match element.value().name() {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Indique si l'élément marque le début d'une nouvelle section
+fn is_heading_block(element: &ElementRef) -> bool {
+    heading_level(element).is_some() || element.value().classes().any(|c| c == "mw-heading")
+}
+
+/// Blocs ignorés par le texte intégral : tableaux, infobox, images, sommaire,
+/// bandeaux, navigation et notes
+const FULL_TEXT_SKIP_SELECTOR: &str = "table, figure, style, script, .infobox, .navbox, .thumb, .toc, \
+     .hatnote, .bandeau-portail, .metadata, .noprint, .reflist, ol.references, .mw-editsection";
+
+/// Texte intégral du corps de l'article (`div.mw-parser-output`), dans l'ordre :
+/// paragraphes séparés par une ligne vide, titres précédés de `#` selon leur
+/// niveau et éléments de liste en `- ` (ou `1. `), indentés selon l'imbrication
+fn extract_full_text(document: &Html) -> String {
+    let container_selector = Selector::parse("div.mw-parser-output").unwrap();
+    let Some(container) = document.select(&container_selector).next() else {
+        return String::new();
+    };
+    let skip = Selector::parse(FULL_TEXT_SKIP_SELECTOR).unwrap();
+
+    // (texte, élément de liste) : deux éléments de liste consécutifs ne sont
+    // séparés que par un retour à la ligne
+    let mut blocks: Vec<(String, bool)> = Vec::new();
+    collect_full_text(container, &skip, 0, &mut blocks);
+
+    let mut text = String::new();
+    let mut previous_item = false;
+    for (block, item) in blocks {
+        if !text.is_empty() {
+            text.push_str(if previous_item && item { "\n" } else { "\n\n" });
+        }
+        text.push_str(&block);
+        previous_item = item;
+    }
+    text
+}
+
+/// Parcourt les enfants de `element` et ajoute leurs blocs de texte à `blocks`
+fn collect_full_text(element: ElementRef, skip: &Selector, list_depth: usize, blocks: &mut Vec<(String, bool)>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        if skip.matches(&child) {
+            continue;
+        }
+        let name = child.value().name();
+        if let Some(level) = heading_level(&child) {
+            let title = collapse_whitespace(&prose_text(&child));
+            if !title.is_empty() {
+                blocks.push((format!("{} {}", "#".repeat(level as usize), title), false));
+            }
+        } else if matches!(name, "p" | "dt" | "dd") {
+            let text = collapse_whitespace(&prose_text(&child));
+            if !text.is_empty() {
+                blocks.push((text, false));
+            }
+        } else if matches!(name, "ul" | "ol") {
+            let ordered = name == "ol";
+            let items = child.children().filter_map(ElementRef::wrap).filter(|li| li.value().name() == "li");
+            for (number, item) in items.enumerate() {
+                if skip.matches(&item) {
+                    continue;
+                }
+                let marker = if ordered { format!("{}.", number + 1) } else { "-".to_string() };
+                let noise = format!("{}, ul, ol", PROSE_NOISE_SELECTOR);
+                let text = collapse_whitespace(&prose_text_without(&item, &noise));
+                if !text.is_empty() {
+                    blocks.push((format!("{}{} {}", "  ".repeat(list_depth), marker, text), true));
+                }
+                // Sous-listes, indentées sous l'élément
+                collect_full_text(item, skip, list_depth + 1, blocks);
+            }
+        } else {
+            collect_full_text(child, skip, list_depth, blocks);
+        }
+    }
+}
+
+/// Détecte une page d'homonymie : bandeau `#homonymie` (fr), boîte
+/// `disambigbox` (en) ou catégorie « Homonymie »
+fn is_disambiguation_page(document: &Html) -> bool {
+    let banner_selector = Selector::parse(
+        "#homonymie, .homonymie, .bandeau-homonymie, #disambigbox, .disambigbox",
+    )
+    .unwrap();
+    // Le bandeau « Pour les articles homonymes, voir... » des articles porte aussi
+    // la classe `homonymie`, mais c'est un hatnote et non une page d'homonymie
+    if document
+        .select(&banner_selector)
+        .any(|el| !el.value().classes().any(|c| c == "hatnote"))
+    {
+        return true;
+    }
+
+    let category_selector = Selector::parse("#mw-normal-catlinks ul li a").unwrap();
+    document.select(&category_selector).any(|el| {
+        let category = el.text().collect::<String>().to_lowercase();
+        category.starts_with("homonymie") || category.starts_with("disambiguation pages")
+    })
+}
+
+/// Liste les articles proposés par une page d'homonymie (liens `/wiki/` des listes)
+fn extract_disambiguation_options(document: &Html, host: &str) -> Vec<String> {
+    let option_selector = Selector::parse("div.mw-parser-output li a[href^='/wiki/']").unwrap();
+    let mut seen = HashSet::new();
+
+    document
+        .select(&option_selector)
+        .filter_map(|el| el.value().attr("href"))
+        .filter(|href| !href.contains(':') && !href.contains('#'))
+        .map(|href| format!("https://{}{}", host, canonical_wiki_path(href)))
+        .filter(|url| seen.insert(url.to_lowercase()))
+        .collect()
+}
+
+/// Liste les liens interlangues de la barre latérale (`#p-lang`, `.interlanguage-link`) :
+/// code de la langue (`hreflang`, à défaut `lang`) et URL de l'article dans cette langue
+fn extract_languages(document: &Html) -> Vec<(String, String)> {
+    let language_selector = Selector::parse("#p-lang li a[href], .interlanguage-link a[href]").unwrap();
+    let mut seen = HashSet::new();
+
+    document
+        .select(&language_selector)
+        .filter_map(|el| {
+            let code = el.value().attr("hreflang").or_else(|| el.value().attr("lang"))?.trim();
+            let href = el.value().attr("href")?.trim();
+            // Les liens interlangues sont protocol-relative (`//en.wikipedia.org/wiki/...`)
+            let url = if href.starts_with("//") {
+                format!("https:{}", href)
+            } else {
+                href.to_string()
+            };
+            let url = match url.split_once("/wiki/") {
+                Some((origin, _)) => format!("{}{}", origin, canonical_wiki_path(&url[origin.len()..])),
+                None => url,
+            };
+            (!code.is_empty() && url.starts_with("http")).then(|| (code.to_string(), url))
+        })
+        .filter(|(code, _)| seen.insert(code.clone()))
+        .collect()
+}
+
+/// Domaines des projets Wikimedia, dont les liens ne sont pas des sources externes
+const WIKIMEDIA_DOMAINS: [&str; 12] = [
+    "wikipedia.org",
+    "wikimedia.org",
+    "wikidata.org",
+    "wiktionary.org",
+    "wikisource.org",
+    "wikibooks.org",
+    "wikiquote.org",
+    "wikinews.org",
+    "wikiversity.org",
+    "wikivoyage.org",
+    "mediawiki.org",
+    "wikimediafoundation.org",
+];
+
+/// Liste les liens externes du contenu (`a.external`), sans doublon, en excluant
+/// les liens vers les projets Wikimedia (Commons, Wikidata, autres Wikipedia...)
+fn extract_external_links(document: &Html) -> Vec<String> {
+    let external_selector = Selector::parse("#mw-content-text a.external[href^='http']").unwrap();
+    let mut seen = HashSet::new();
+
+    document
+        .select(&external_selector)
+        .filter_map(|el| el.value().attr("href"))
+        .map(|href| href.trim().to_string())
+        .filter(|href| {
+            let host = parse_url(href).map(|url| url.host.to_lowercase()).unwrap_or_default();
+            !WIKIMEDIA_DOMAINS
+                .iter()
+                .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+        })
+        .filter(|href| seen.insert(href.clone()))
+        .collect()
+}
+
+/// Tronque une liste à `max` éléments ; 0 signifie « pas de limite »
+fn truncate_to_limit<T>(items: &mut Vec<T>, max: usize) {
+    if max > 0 {
+        items.truncate(max);
+    }
+}
+
+/// Extrait les coordonnées décimales du span `.geo` (format `lat; lon`)
+///
+/// Le span se trouve dans le bloc `#coordinates` ou dans l'infobox ; on retourne
+/// `None` pour les articles sans coordonnées ou si les valeurs sont illisibles.
+fn extract_coordinates(document: &Html) -> Option<(f64, f64)> {
+    let geo_selector = Selector::parse("#coordinates .geo, .geo").unwrap();
+    let geo = document.select(&geo_selector).next()?;
+    let text = geo.text().collect::<String>();
+
+    let (lat, lon) = text.split_once(';')?;
+    let lat = lat.trim().parse::<f64>().ok()?;
+    let lon = lon.trim().parse::<f64>().ok()?;
+
+    if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+        Some((lat, lon))
+    } else {
+        None
+    }
+}
+
+/// Nombre maximum de références conservées par article
+const MAX_REFERENCES: usize = 200;
+
+/// Extrait les références de `ol.references li`
+///
+/// Les flèches de renvoi (`↑`, `^`) sont retirées ; l'URL du premier lien
+/// externe est ajoutée entre crochets si elle n'apparaît pas déjà dans le texte.
+fn extract_references(document: &Html) -> Vec<String> {
+    let reference_selector = Selector::parse("ol.references > li").unwrap();
+    let backlink_selector = Selector::parse(".mw-cite-backlink").unwrap();
+    let external_selector = Selector::parse("a.external[href]").unwrap();
+
+    document
+        .select(&reference_selector)
+        .filter_map(|li| {
+            let backlinks: String = li
+                .select(&backlink_selector)
+                .flat_map(|el| el.text())
+                .collect();
+            let mut text: String = li.text().collect();
+            if !backlinks.is_empty() {
+                text = text.replacen(&backlinks, " ", 1);
+            }
+            let text = collapse_whitespace(&decode_entities(&text).replace(['↑', '^'], " "));
+            if text.is_empty() {
+                return None;
+            }
+
+            let external_url = li
+                .select(&external_selector)
+                .filter_map(|a| a.value().attr("href"))
+                .map(|href| if href.starts_with("//") { format!("https:{}", href) } else { href.to_string() })
+                .next();
+
+            match external_url {
+                Some(url) if !text.contains(&url) => Some(format!("{} [{}]", text, url)),
+                _ => Some(text),
+            }
+        })
+        .take(MAX_REFERENCES)
+        .collect()
+}
+
+/// URL absolue d'une image d'article hébergée sur upload.wikimedia.org, ou `None`
+/// pour les icônes, logos et images de moins de `min_size` pixels (d'après les
+/// attributs `width` / `height` s'ils sont présents, et la largeur de la miniature)
+///
+/// Parmi les candidats du `srcset` (`//url 1.5x, //url2 2x`), celui de plus forte
+/// densité qui passe les filtres est préféré à `src`.
+fn article_image_url(el: &ElementRef, min_size: u32) -> Option<String> {
+    let width = el.value().attr("width");
+    let height = el.value().attr("height");
+    
+    if let (Some(w), Some(h)) = (width, height) {
+        if let (Ok(w_num), Ok(h_num)) = (w.parse::<u32>(), h.parse::<u32>()) {
+            if w_num < min_size || h_num < min_size {
+                return None;
+            }
+        }
+    }
+
+    let mut candidates = el.value().attr("srcset").map(parse_srcset).unwrap_or_default();
+    candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    candidates
+        .into_iter()
+        .map(|(url, _)| url)
+        .chain(el.value().attr("src"))
+        .find_map(|src| filter_image_src(src, min_size))
+}
+
+/// Candidats d'un attribut `srcset` avec leur densité (`1.5x`, `1x` si absente) ;
+/// les descripteurs de largeur (`300w`) ne sont pas comparables et sont ignorés
+fn parse_srcset(srcset: &str) -> Vec<(&str, f32)> {
+    srcset
+        .split(',')
+        .filter_map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            let url = parts.next()?;
+            let density = match parts.next() {
+                None => 1.0,
+                Some(descriptor) => descriptor.strip_suffix('x')?.parse::<f32>().ok()?,
+            };
+            Some((url, density))
+        })
+        .collect()
+}
+
+/// URL absolue (`//...` → `https://...`) d'une image upload.wikimedia.org,
+/// ou `None` pour les icônes, logos et miniatures de moins de `min_size` pixels
+fn filter_image_src(src: &str, min_size: u32) -> Option<String> {
+    if !(src.starts_with("//") || src.starts_with("http")) {
+        return None;
+    }
+    
+    if !(src.contains(".jpg") || src.contains(".jpeg") || 
+         src.contains(".png") || src.contains(".svg") || src.contains(".gif")) {
+        return None;
+    }
+    
+    if src.contains("/static/images/") || src.contains("/icons/") ||
+       src.contains("Icon_") || src.contains("icon") || src.contains("logo") {
+        return None;
+    }
+    if thumbnail_width(src).is_some_and(|width| width < min_size) {
+        return None;
+    }
+    
+    let img_url = if src.starts_with("//") {
+        format!("https:{}", src)
+    } else {
+        src.to_string()
+    };
+    
+    if img_url.contains("upload.wikimedia.org") {
+        Some(img_url)
+    } else {
+        None
+    }
+}
+
+/// Réécrit une URL de miniature upload.wikimedia.org
+/// (`.../thumb/a/ab/Fichier.jpg/220px-Fichier.jpg`) selon la taille demandée ;
+/// les autres URLs sont renvoyées inchangées
+fn resize_image_url(url: &str, size: ImageSize) -> String {
+    let Some((base, thumb_name)) = url.rsplit_once('/') else {
+        return url.to_string();
+    };
+    let Some(original) = base.split_once("/thumb/").map(|(start, path)| format!("{}/{}", start, path)) else {
+        return url.to_string();
+    };
+    // Le nom de la miniature commence par sa largeur : « 220px-Fichier.jpg »
+    let Some(file_name) = thumb_name
+        .split_once("px-")
+        .filter(|(width, _)| !width.is_empty() && width.chars().all(|c| c.is_ascii_digit()))
+        .map(|(_, name)| name)
+    else {
+        return url.to_string();
+    };
+
+    match size {
+        ImageSize::AsPage => url.to_string(),
+        ImageSize::Width(width) => format!("{}/{}px-{}", base, width, file_name),
+        ImageSize::Original => original,
+    }
+}
+
+/// Légende de la figure qui contient l'image : `figure > figcaption`, ou
+/// `.thumbcaption` de l'ancien balisage `div.thumb`
+fn image_caption(el: &ElementRef) -> String {
+    let figcaption_selector = Selector::parse("figcaption").unwrap();
+    let thumbcaption_selector = Selector::parse(".thumbcaption").unwrap();
+
+    el.ancestors()
+        .filter_map(ElementRef::wrap)
+        .find_map(|ancestor| match ancestor.value().name() {
+            "figure" => ancestor.select(&figcaption_selector).next(),
+            "div" if ancestor.value().classes().any(|class| class == "thumb") => {
+                ancestor.select(&thumbcaption_selector).next()
+            }
+            _ => None,
+        })
+        .map(|caption| collapse_whitespace(&caption.text().collect::<String>()))
+        .unwrap_or_default()
+}
+
+/// Largeur d'une miniature, lue dans son nom (« 220px-Fichier.jpg »)
+fn thumbnail_width(url: &str) -> Option<u32> {
+    let (_, thumb_name) = url.rsplit_once('/')?;
+    let (width, _) = thumb_name.split_once("px-")?;
+    width.parse().ok()
+}
+
+/// Image principale de l'article : la première image de l'infobox, ou à défaut
+/// la première image d'au moins 200 pixels de large du contenu
+fn extract_lead_image(document: &Html, min_size: u32) -> Option<String> {
+    let infobox_selector = Selector::parse("table.infobox img[src]").unwrap();
+    let content_selector = Selector::parse(".mw-parser-output img[src]").unwrap();
+
+    document
+        .select(&infobox_selector)
+        .find_map(|el| article_image_url(&el, min_size))
+        .or_else(|| {
+            document
+                .select(&content_selector)
+                .filter(|el| {
+                    el.value()
+                        .attr("width")
+                        .and_then(|w| w.parse::<u32>().ok())
+                        .is_some_and(|w| w >= 200)
+                })
+                .find_map(|el| article_image_url(&el, min_size))
+        })
+}
+
+/// Extrait les tableaux `table.wikitable` ligne par ligne (cellules `th` et `td`)
+///
+/// Les fusions (colspan/rowspan) ne sont pas reproduites : chaque cellule compte
+/// pour une colonne. Les cellules qui ne contiennent qu'une image sont ignorées.
+fn extract_tables(document: &Html) -> Vec<Vec<Vec<String>>> {
+    let table_selector = Selector::parse("table.wikitable").unwrap();
+    let row_selector = Selector::parse("tr").unwrap();
+    let image_selector = Selector::parse("img").unwrap();
+
+    document
+        .select(&table_selector)
+        .map(|table| {
+            table
+                .select(&row_selector)
+                .map(|row| {
+                    row.children()
+                        .filter_map(ElementRef::wrap)
+                        .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                        .filter_map(|cell| {
+                            let text = collapse_whitespace(&decode_entities(&prose_text(&cell)));
+                            if text.is_empty() && cell.select(&image_selector).next().is_some() {
+                                None
+                            } else {
+                                Some(text)
+                            }
+                        })
+                        .collect::<Vec<String>>()
+                })
+                .filter(|cells| cells.iter().any(|cell| !cell.is_empty()))
+                .collect::<Vec<_>>()
+        })
+        .filter(|rows| !rows.is_empty())
+        .collect()
+}
+
+/// Extrait l'infobox (`table.infobox`) sous forme de paires libellé / valeur
+///
+/// Chaque ligne associe son `th` à son `td` ; les lignes sans libellé ou sans
+/// valeur (titres, images, séparateurs) sont ignorées.
+fn extract_infobox(document: &Html) -> Vec<(String, String)> {
+    let row_selector = Selector::parse("table.infobox tr").unwrap();
+    let label_selector = Selector::parse("th").unwrap();
+    let value_selector = Selector::parse("td").unwrap();
+
+    document
+        .select(&row_selector)
+        .filter_map(|row| {
+            let label = row.select(&label_selector).next()?;
+            let value = row.select(&value_selector).next()?;
+
+            let label = collapse_whitespace(&decode_entities(&label.text().collect::<String>()));
+            let value = collapse_whitespace(&decode_entities(&value.text().collect::<String>()));
+
+            if label.is_empty() || value.is_empty() {
+                None
+            } else {
+                Some((label, value))
+            }
+        })
+        .collect()
+}
+
+/// Remplace toute suite d'espaces (retours à la ligne compris) par un espace simple
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Décode les entités HTML résiduelles (`&amp;`, `&#39;`, `&nbsp;`...) et retire
+/// les espaces superflus ; un texte sans entité est renvoyé tel quel
+fn decode_entities(text: &str) -> String {
+    html_escape::decode_html_entities(text).trim().to_string()
+}
+
+/// Éléments éditoriaux insérés dans la prose : appels de notes, « [réf. nécessaire] »,
+/// liens « modifier »
+const PROSE_NOISE_SELECTOR: &str = "sup.reference, sup.need_ref_tag, sup.noprint, span.mw-editsection";
+
+/// Mentions éditoriales entre crochets retirées du texte, en plus des appels de notes numériques
+const EDITORIAL_BRACKETS: [&str; 6] = [
+    "réf. nécessaire",
+    "réf. souhaitée",
+    "modifier",
+    "modifier | modifier le code",
+    "citation needed",
+    "edit",
+];
+
+/// Texte d'un élément sans les appels de notes ni les mentions éditoriales
+fn prose_text(element: &ElementRef) -> String {
+    prose_text_without(element, PROSE_NOISE_SELECTOR)
+}
+
+/// Comme [`prose_text`], en retirant les éléments désignés par `noise`
+fn prose_text_without(element: &ElementRef, noise: &str) -> String {
+    let noise_selector = Selector::parse(noise).unwrap();
+    let noise: HashSet<_> = element.select(&noise_selector).map(|el| el.id()).collect();
+    let text: String = element
+        .descendants()
+        .filter(|node| !node.ancestors().any(|ancestor| noise.contains(&ancestor.id())))
+        .filter_map(|node| node.value().as_text().map(|text| text.to_string()))
+        .collect();
+    strip_citation_markers(&text)
+}
+
+/// Retire les marqueurs `[12]` et les mentions éditoriales connues restés dans le texte ;
+/// les autres contenus entre crochets font partie de la prose et sont conservés
+fn strip_citation_markers(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        result.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let marker = after.find(']').map(|close| &after[..close]).filter(|inner| {
+            let inner = inner.trim();
+            (!inner.is_empty() && inner.chars().all(|c| c.is_ascii_digit()))
+                || EDITORIAL_BRACKETS.iter().any(|known| inner.eq_ignore_ascii_case(known))
+        });
+        match marker {
+            Some(inner) => {
+                rest = &after[inner.len() + 1..];
+                // Pas de double espace ni d'espace avant un point là où était le marqueur
+                if result.ends_with(' ') {
+                    rest = rest.strip_prefix(' ').unwrap_or(rest);
+                    if rest.starts_with(['.', ',']) {
+                        result.pop();
+                    }
+                }
+            }
+            None => {
+                result.push('[');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn extract_summary(document: &Html) -> (String, String) {
+    // On cible le conteneur principal du contenu de l'article.
+    if let Some(container) = document.select(&Selector::parse("div.mw-parser-output").unwrap()).next() {
+        let mut summary_parts: Vec<String> = Vec::new();
+        let h2_selector = Selector::parse("h2").unwrap();
+
+        // On parcourt tous les nœuds enfants directs du conteneur.
+        for node in container.children() {
+            if let Some(elem) = ElementRef::wrap(node) {
+                let tag_name = elem.value().name.local.as_ref();
+
+                // C'est le marqueur de la fin du résumé.
+                // On arrête si l'élément est un <h2> ou s'il contient un <h2>.
+                if tag_name == "h2" || elem.select(&h2_selector).next().is_some() {
+                    break;
+                }
+
+                // On ne garde que le texte des balises <p>.
+                if tag_name == "p" {
+                    let paragraph_text = prose_text(&elem).trim().to_string();
+                    
+                    // On s'assure que le paragraphe n'est pas vide.
+                    if !paragraph_text.is_empty() {
+                        summary_parts.push(paragraph_text);
+                    }
+                }
+            }
+        }
+
+        // On assemble les paragraphes collectés ; le premier sert de chapeau (`lead`).
+        if let Some(lead) = summary_parts.first() {
+            return (summary_parts.join("\n\n"), lead.clone());
+        }
+    }
+
+    // Fallback si aucun résumé n'est trouvé.
+    (String::new(), String::new())
+}
+
+/// Effectue une requête GET vers l'URL donnée, en HTTP ou HTTPS selon son schéma
+///
+/// Renvoie le body décodé en texte et l'URL finale atteinte après les éventuelles
+/// redirections.
+pub fn http_get(target: &UrlParts, config: &HttpConfig) -> Result<(String, UrlParts), ScraperError> {
+    let fetched = http_get_bytes(target, config)?;
+    let final_url = fetched.final_url.clone();
+    Ok((fetched.into_text(), final_url))
+}
+
+/// Effectue une requête GET et renvoie le body brut, sans conversion en texte
+/// (indispensable pour les contenus binaires comme les images)
+///
+/// Les erreurs transitoires sont retentées avec un délai exponentiel
+/// (retry_delay, 2 × retry_delay, 4 × retry_delay...).
+fn http_get_bytes(target: &UrlParts, config: &HttpConfig) -> Result<FetchedBody, ScraperError> {
+    http_get_conditional(target, None, config)
+}
+
+/// Variante de `http_get_bytes` qui envoie `If-None-Match` / `If-Modified-Since`
+/// à partir des validateurs fournis ; un 304 donne un body vide avec `not_modified`
+fn http_get_conditional(
+    target: &UrlParts,
+    validators: Option<&Validators>,
+    config: &HttpConfig,
+) -> Result<FetchedBody, ScraperError> {
+    let mut attempt = 0;
+    loop {
+        match http_get_with_depth(target, validators, config, 0) {
+            Ok(fetched) => return Ok(fetched),
+            // Limitation de débit : attendre le délai demandé par le serveur plutôt que
+            // le délai exponentiel, sauf s'il est déraisonnable (voir `MAX_RETRY_AFTER`)
+            Err(ScraperError::HttpStatus { code, retry_after: Some(wait), .. })
+                if attempt < config.retries && wait <= MAX_RETRY_AFTER =>
+            {
+                attempt += 1;
+                warn!(
+                    "  ⏳ Requêtes limitées par le serveur ({}), attente de {} s avant la tentative {}/{}",
+                    code,
+                    wait.as_secs(),
+                    attempt + 1,
+                    config.retries + 1
+                );
+                std::thread::sleep(wait);
+            }
+            Err(e) if attempt < config.retries && e.is_retryable() => {
+                // Plafonné à `MAX_RETRY_AFTER` : `--retry-delay` et `--retries` élevés
+                // ne doivent ni déborder ni donner une attente démesurée
+                let delay = config
+                    .retry_delay
+                    .checked_mul(2u32.saturating_pow(attempt))
+                    .map_or(MAX_RETRY_AFTER, |d| d.min(MAX_RETRY_AFTER));
+                attempt += 1;
+                warn!(
+                    "  ↻ Tentative {}/{} échouée ({}), nouvel essai dans {} ms",
+                    attempt,
+                    config.retries + 1,
+                    e,
+                    delay.as_millis()
+                );
+                std::thread::sleep(delay);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Attente maximale acceptée pour un header `Retry-After` ; au-delà, on revient au
+/// délai exponentiel habituel entre deux tentatives, lui-même plafonné à cette valeur
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(600);
+
+/// Interprète la valeur d'un header `Retry-After` : un nombre de secondes ou une
+/// date HTTP (`Wed, 21 Oct 2015 07:28:00 GMT`) ; une date passée donne une attente nulle
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Effectue une requête HTTPS GET (raccourci de `http_get_bytes` pour un hôte connu)
+///
+/// Le body est renvoyé brut : c'est la base des téléchargements binaires.
+fn https_get_bytes(host: &str, port: u16, path: &str, config: &HttpConfig) -> Result<FetchedBody, ScraperError> {
+    let target = UrlParts {
+        scheme: Scheme::Https,
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+    };
+    http_get_bytes(&target, config)
+}
+
+/// Effectue une requête HTTPS GET et décode le body en texte selon son charset
+pub fn https_get(host: &str, port: u16, path: &str, config: &HttpConfig) -> Result<String, ScraperError> {
+    https_get_bytes(host, port, path, config).map(FetchedBody::into_text)
+}
+
+/// Effectue une seule tentative de requête, en suivant les redirections
+///
+/// `depth` compte les redirections déjà suivies ; au-delà de `max_redirects`
+/// la requête échoue plutôt que de boucler indéfiniment.
+fn http_get_with_depth(
+    target: &UrlParts,
+    validators: Option<&Validators>,
+    config: &HttpConfig,
+    depth: u32,
+) -> Result<FetchedBody, ScraperError> {
+    config.rate_limiter.acquire(&target.host);
+    debug!("  → GET {}", target);
+
+    let request = build_request(target, &config.user_agent, validators, config.proxy.as_ref());
+    let response = exchange(target, &request, config)?;
+
+    let headers = response.headers;
+
+    let status = StatusLine::parse(headers.lines().next().unwrap_or(""))?;
+
+    if status.is_redirect() {
+        if let Some(location) = extract_header(&headers, "Location") {
+            if depth >= config.max_redirects {
+                return Err(ScraperError::Redirect(format!(
+                    "trop de redirections (plus de {}) depuis {}",
+                    config.max_redirects, target.host
+                )));
+            }
+            let redirect_target = resolve_location(target, &location)?;
+            debug!("  ↪ {} : {} → {}", status.code, target, redirect_target);
+            // Seul GET est émis : 301/302/303 (passage en GET) et 307/308 (méthode
+            // conservée) se suivent donc de la même façon
+            return http_get_with_depth(&redirect_target, validators, config, depth + 1);
+        }
+    }
+
+    if status.code == 304 {
+        return Ok(FetchedBody {
+            bytes: Vec::new(),
+            charset: None,
+            final_url: target.clone(),
+            validators: Validators::from_headers(&headers),
+            not_modified: true,
+        });
+    }
+
+    let body = response.body;
+    let charset = extract_header(&headers, "Content-Type").and_then(|ct| charset_from_content_type(&ct));
+
+    // Les erreurs serveur (5xx) sont souvent passagères : `is_retryable` les retente
+    if !status.is_success() {
+        // Le début de la page d'erreur explique souvent le refus (IP bloquée, limite de débit...)
+        let body = match extract_header(&headers, "Content-Encoding") {
+            Some(encoding) => decompress_body(&body, &encoding).unwrap_or_default(),
+            None => body,
+        };
+        let body = error_snippet(&bytes_to_string(body, charset.as_deref()));
+        let retry_after = match status.code {
+            429 | 503 => extract_header(&headers, "Retry-After").and_then(|value| parse_retry_after(&value)),
+            _ => None,
+        };
+        return Err(ScraperError::HttpStatus {
+            code: status.code,
+            status: status.to_string(),
+            body,
+            retry_after,
+        });
+    }
+
+    // Décompresser le body selon le Content-Encoding annoncé par le serveur
+    let body = match extract_header(&headers, "Content-Encoding") {
+        Some(encoding) => decompress_body(&body, &encoding)?,
+        None => body,
+    };
+
+    Ok(FetchedBody {
+        bytes: body,
+        charset,
+        final_url: target.clone(),
+        validators: Validators::from_headers(&headers),
+        not_modified: false,
+    })
+}
+
+/// Nombre maximal de caractères du body repris dans une erreur HTTP
+const ERROR_SNIPPET_CHARS: usize = 300;
+
+/// Réduit le body d'une réponse d'erreur à un court extrait lisible : texte seul
+/// (sans balises, scripts ni styles), espaces regroupés, tronqué à `ERROR_SNIPPET_CHARS`
+fn error_snippet(body: &str) -> String {
+    let text = if body.trim_start().starts_with('<') {
+        let document = Html::parse_document(body);
+        let root = document
+            .select(&Selector::parse("body").unwrap())
+            .next()
+            .unwrap_or_else(|| document.root_element());
+        root.descendants()
+            .filter_map(|node| {
+                let text = node.value().as_text()?;
+                let parent = node.parent().and_then(ElementRef::wrap)?;
+                (!matches!(parent.value().name(), "script" | "style")).then(|| text.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        body.to_string()
+    };
+
+    let text = collapse_whitespace(&text);
+    if text.chars().count() > ERROR_SNIPPET_CHARS {
+        let mut snippet: String = text.chars().take(ERROR_SNIPPET_CHARS).collect();
+        snippet.push('…');
+        snippet
+    } else {
+        text
+    }
+}
+
+/// Extrait la valeur du paramètre `charset=` d'un header Content-Type
+fn charset_from_content_type(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case("charset") {
+            Some(value.trim().trim_matches('"').to_lowercase())
+        } else {
+            None
+        }
+    })
+}
+
+/// Convertit le body en texte selon le charset annoncé (UTF-8 par défaut)
+///
+/// Un décodage UTF-8 « lossy » remplacerait chaque accent d'une page Latin-1
+/// par le caractère �, alors que chaque octet Latin-1 correspond directement
+/// au point de code Unicode de même valeur : c'est donc aussi le repli quand
+/// un body censé être en UTF-8 ne l'est pas.
+fn bytes_to_string(bytes: Vec<u8>, charset: Option<&str>) -> String {
+    let latin1 = |bytes: &[u8]| bytes.iter().map(|&b| b as char).collect::<String>();
+
+    match charset {
+        Some("iso-8859-1" | "iso-8859-15" | "latin1" | "latin-1" | "windows-1252") => latin1(&bytes),
+        _ => match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(e) => latin1(e.as_bytes()),
+        },
+    }
+}
+
+/// Envoie la requête en réutilisant si possible une connexion persistante du pool
+///
+/// La connexion est remise dans le pool après la réponse si le serveur accepte de
+/// la garder ouverte.
+fn exchange(target: &UrlParts, request: &str, config: &HttpConfig) -> Result<HttpResponse, ScraperError> {
+    if let Some(mut transport) = config.pool.take(target) {
+        if let Ok(response) = send_request(&mut transport, request, target, config) {
+            if response.keep_alive {
+                config.pool.put(target, transport);
+            }
+            return Ok(response);
+        }
+        // Le serveur a pu fermer la connexion inactive : on en ouvre une nouvelle
+    }
+
+    let mut transport = open_transport(target, config)?;
+    let response = send_request(&mut transport, request, target, config)?;
+    if response.keep_alive {
+        config.pool.put(target, transport);
+    }
+    Ok(response)
+}
+
+/// Ouvre une nouvelle connexion vers la cible, chiffrée en TLS pour `https://`
+fn open_transport(target: &UrlParts, config: &HttpConfig) -> Result<Transport, ScraperError> {
+    // Avec un proxy, la connexion TCP s'ouvre vers lui et non vers la cible
+    let mut sock = match &config.proxy {
+        Some(proxy) => connect_with_timeout(&proxy.host, proxy.port, config.timeout)?,
+        None => connect_with_timeout(&target.host, target.port, config.timeout)?,
+    };
+    sock.set_read_timeout(Some(config.timeout))?;
+    sock.set_write_timeout(Some(config.timeout))?;
+
+    match target.scheme {
+        Scheme::Https => {
+            if let Some(proxy) = &config.proxy {
+                open_tunnel(&mut sock, target, proxy, &config.user_agent)?;
+            }
+
+            let tls_config = tls_client_config(config)?;
+            let server_name = ServerName::try_from(unbracketed_host(&target.host))
+                .map_err(|e| ScraperError::Tls(format!("{} ({})", e, target.host)))?
+                .to_owned();
+            let mut conn = rustls::ClientConnection::new(Arc::new(tls_config), server_name)
+                .map_err(|e| ScraperError::Tls(e.to_string()))?;
+
+            // Poignée de main immédiate pour vérifier le protocole négocié avant d'envoyer
+            // la requête : le parseur ne comprend que le texte de HTTP/1.1
+            while conn.is_handshaking() {
+                conn.complete_io(&mut sock)?;
+            }
+            if let Some(protocol) = conn.alpn_protocol().filter(|protocol| *protocol != HTTP_1_1_ALPN) {
+                return Err(ScraperError::Tls(format!(
+                    "{} a négocié le protocole {} au lieu de http/1.1",
+                    target.host,
+                    String::from_utf8_lossy(protocol)
+                )));
+            }
+            Ok(Transport::Tls(Box::new(rustls::StreamOwned::new(conn, sock))))
+        }
+        Scheme::Http => Ok(Transport::Plain(sock)),
+    }
+}
+
+/// Identifiant ALPN de HTTP/1.1, le seul protocole annoncé au serveur
+const HTTP_1_1_ALPN: &[u8] = b"http/1.1";
+
+/// Configuration TLS du client : racines webpki et certificats de `--ca-file`,
+/// ou aucune vérification avec `insecure`
+///
+/// L'ALPN n'annonce que `http/1.1`, pour qu'un serveur compatible HTTP/2 ne bascule
+/// pas sur un protocole binaire que le parseur de réponses ne sait pas lire.
+fn tls_client_config(config: &HttpConfig) -> Result<rustls::ClientConfig, ScraperError> {
+    if config.insecure {
+        let mut tls_config = rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+            .with_no_client_auth();
+        tls_config.alpn_protocols = vec![HTTP_1_1_ALPN.to_vec()];
+        return Ok(tls_config);
+    }
+
+    let mut root_store = rustls::RootCertStore::empty();
+    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    for cert in &config.extra_root_certs {
+        root_store
+            .add(cert.clone())
+            .map_err(|e| ScraperError::Tls(format!("certificat d'autorité refusé ({})", e)))?;
+    }
+    let mut tls_config = rustls::ClientConfig::builder()
+        .with_root_certificates(root_store)
+        .with_no_client_auth();
+    tls_config.alpn_protocols = vec![HTTP_1_1_ALPN.to_vec()];
+    Ok(tls_config)
+}
+
+/// Lit les certificats PEM d'un fichier d'autorités (`--ca-file`) ; un fichier
+/// sans aucun bloc `CERTIFICATE` est une erreur
+pub fn load_ca_file(path: &str) -> Result<Vec<rustls::pki_types::CertificateDer<'static>>, ScraperError> {
+    let file = fs::File::open(path)?;
+    let certs = rustls_pemfile::certs(&mut io::BufReader::new(file)).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(ScraperError::Tls(format!("aucun certificat PEM dans {}", path)));
+    }
+    Ok(certs)
+}
+
+/// Vérificateur qui accepte tous les certificats et toutes les signatures (`--insecure`) :
+/// la connexion reste chiffrée mais n'importe qui peut se faire passer pour le serveur
+#[derive(Debug)]
+struct NoCertificateVerification;
+
+impl rustls::client::danger::ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        rustls::crypto::ring::default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// Ouvre un tunnel `CONNECT hôte:port` à travers le proxy ; la poignée de main TLS
+/// avec la cible se fait ensuite dans ce tunnel
+fn open_tunnel(sock: &mut TcpStream, target: &UrlParts, proxy: &Proxy, user_agent: &str) -> Result<(), ScraperError> {
+    let authority = format!("{}:{}", target.host, target.port);
+    let mut request = format!(
+        "CONNECT {0} HTTP/1.1\r\nHost: {0}\r\nUser-Agent: {1}\r\n",
+        authority, user_agent
+    );
+    if let Some(authorization) = &proxy.authorization {
+        request.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+    }
+    request.push_str("\r\n");
+    debug!("  → CONNECT {} via {}:{}", authority, proxy.host, proxy.port);
+    sock.write_all(request.as_bytes())?;
+
+    // Lecture octet par octet : les octets suivant les headers appartiennent au tunnel
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if sock.read(&mut byte)? == 0 {
+            return Err(ScraperError::Connection(format!(
+                "Le proxy {}:{} a fermé la connexion pendant le CONNECT",
+                proxy.host, proxy.port
+            )));
+        }
+        response.push(byte[0]);
+        if response.len() > 64 * 1024 {
+            return Err(ScraperError::Parse("Réponse du proxy trop longue".to_string()));
+        }
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status = StatusLine::parse(response.lines().next().unwrap_or(""))?;
+    if !status.is_success() {
+        // 407 : identifiants absents ou refusés ; 5xx : cible injoignable depuis le proxy
+        return Err(ScraperError::HttpStatus {
+            code: status.code,
+            status: format!("{} (proxy {}:{})", status, proxy.host, proxy.port),
+            body: String::new(),
+            retry_after: None,
+        });
+    }
+    Ok(())
+}
+
+/// Hôte sans les crochets d'une adresse IPv6 littérale (`[::1]` → `::1`), tel
+/// qu'attendu pour la résolution d'adresse et le nom de serveur TLS
+fn unbracketed_host(host: &str) -> &str {
+    host.strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+}
+
+/// Règles d'un robots.txt applicables à notre User-Agent
+#[derive(Debug, Default)]
+struct RobotsRules {
+    /// (autorisé, motif de chemin) pour chaque directive Allow / Disallow
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Analyse un robots.txt et ne garde que le groupe qui nous concerne : celui
+    /// dont le User-agent figure dans notre User-Agent, sinon le groupe `*`
+    fn parse(content: &str, user_agent: &str) -> RobotsRules {
+        let user_agent = user_agent.to_lowercase();
+        let mut specific: Vec<(bool, String)> = Vec::new();
+        let mut wildcard: Vec<(bool, String)> = Vec::new();
+        let mut has_specific = false;
+
+        // Agents du groupe en cours ; un nouveau groupe commence après des règles
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_lowercase();
+            let value = value.trim();
+
+            match field.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // « Disallow: » vide signifie « tout est autorisé »
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    if group_agents.iter().any(|a| a != "*" && user_agent.contains(a.as_str())) {
+                        has_specific = true;
+                        specific.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        RobotsRules {
+            rules: if has_specific { specific } else { wildcard },
+        }
+    }
+
+    /// La règle au motif le plus long l'emporte ; à égalité, Allow gagne
+    fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| robots_pattern_matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .map(|(allow, _)| *allow)
+            .unwrap_or(true)
+    }
+}
+
+/// Compare un chemin à un motif robots.txt (préfixe, `*` joker, `$` fin de chemin)
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    if !pattern.contains('*') {
+        return if anchored { path == pattern } else { path.starts_with(pattern) };
+    }
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    // Avec `$`, le dernier fragment du motif doit terminer le chemin
+    !anchored || path.ends_with(pattern.rsplit('*').next().unwrap_or(""))
+}
+
+/// Cache des robots.txt déjà récupérés, par schéma/hôte/port
+#[derive(Default)]
+pub struct RobotsCache {
+    rules: Mutex<HashMap<(Scheme, String, u16), Arc<RobotsRules>>>,
+}
+
+impl RobotsCache {
+    /// Indique si robots.txt autorise le scraping de l'URL ; un robots.txt
+    /// absent ou illisible autorise tout
+    pub fn is_allowed(&self, target: &UrlParts, config: &HttpConfig) -> bool {
+        let key = (target.scheme, target.host.clone(), target.port);
+
+        let cached = self.rules.lock().unwrap_or_else(|e| e.into_inner()).get(&key).cloned();
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let robots_url = UrlParts {
+                    path: "/robots.txt".to_string(),
+                    ..target.clone()
+                };
+                let rules = match http_get(&robots_url, config) {
+                    Ok((content, _)) => RobotsRules::parse(&content, &config.user_agent),
+                    Err(_) => RobotsRules::default(),
+                };
+                let rules = Arc::new(rules);
+                self.rules
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(key, Arc::clone(&rules));
+                rules
+            }
+        };
+
+        rules.is_allowed(&target.path)
+    }
+}
+
+/// Construit manuellement la requête HTTP/1.1 GET
+///
+/// Une requête HTTP passant par un proxy porte l'URL complète et les identifiants
+/// du proxy ; en HTTPS, le proxy ne voit que le tunnel (voir `open_tunnel`).
+fn build_request(
+    target: &UrlParts,
+    user_agent: &str,
+    validators: Option<&Validators>,
+    proxy: Option<&Proxy>,
+) -> String {
+    // Le port n'apparaît dans le header Host que s'il n'est pas celui par défaut
+    let host_header = if target.port == target.scheme.default_port() {
+        target.host.clone()
+    } else {
+        format!("{}:{}", target.host, target.port)
+    };
+
+    // Requête conditionnelle : le serveur répond 304 si la page n'a pas changé
+    let mut conditional = String::new();
+    if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+            conditional.push_str(&format!("If-None-Match: {}\r\n", etag));
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            conditional.push_str(&format!("If-Modified-Since: {}\r\n", last_modified));
+        }
+    }
+
+    let mut request_target = encode_request_path(&target.path);
+    if let (Some(proxy), Scheme::Http) = (proxy, target.scheme) {
+        request_target = format!("http://{}{}", host_header, request_target);
+        if let Some(authorization) = &proxy.authorization {
+            conditional.push_str(&format!("Proxy-Authorization: {}\r\n", authorization));
+        }
+    }
+
+    format!(
+        "GET {} HTTP/1.1\r\n\
+         Host: {}\r\n\
+         User-Agent: {}\r\n\
+         Accept: text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8\r\n\
+         Accept-Language: fr,fr-FR;q=0.8,en-US;q=0.5,en;q=0.3\r\n\
+         Accept-Encoding: gzip, deflate\r\n\
+         Connection: keep-alive\r\n\
+         {}\
+         \r\n",
+        request_target, host_header, user_agent, conditional
+    )
+}
+
+/// Encode les octets non ASCII et les espaces d'un chemin de requête
+/// (les liens extraits sont décodés pour rester lisibles)
+fn encode_request_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_graphic() {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Envoie la requête sur le flux (TCP brut ou TLS) et lit une réponse complète
+///
+/// La fin du body est déterminée par `Transfer-Encoding: chunked` ou
+/// `Content-Length` ; à défaut, la lecture se poursuit jusqu'à la fermeture.
+fn send_request<S: Read + Write>(
+    stream: &mut S,
+    request: &str,
+    target: &UrlParts,
+    config: &HttpConfig,
+) -> Result<HttpResponse, ScraperError> {
+    // Un délai dépassé sur le socket remonte en WouldBlock/TimedOut : on le rend lisible
+    let timeout_error = |e: io::Error| -> ScraperError {
+        if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) {
+            ScraperError::Connection(format!(
+                "Délai d'attente dépassé ({}s) pour {}",
+                config.timeout.as_secs(),
+                target.host
+            ))
+        } else {
+            e.into()
+        }
+    };
+
+    stream.write_all(request.as_bytes()).map_err(timeout_error)?;
+    stream.flush().map_err(timeout_error)?;
+
+    let mut response = Vec::new();
+    let mut buf = [0u8; 8192];
+
+    // Lit un bloc supplémentaire ; renvoie false à la fermeture de la connexion
+    let mut read_more = |response: &mut Vec<u8>| -> Result<bool, ScraperError> {
+        match stream.read(&mut buf) {
+            Ok(0) => Ok(false),
+            Ok(n) => {
+                response.extend_from_slice(&buf[..n]);
+                Ok(true)
+            }
+            // Beaucoup de serveurs ferment la connexion TLS sans close_notify
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(timeout_error(e)),
+        }
+    };
+
+    // Lire jusqu'à la fin des headers
+    let (header_end, separator_len) = loop {
+        if let Some(pos) = find_bytes(&response, b"\r\n\r\n") {
+            break (pos, 4);
+        }
+        if let Some(pos) = find_bytes(&response, b"\n\n") {
+            break (pos, 2);
+        }
+        if !read_more(&mut response)? {
+            return Err(ScraperError::Parse("Impossible de séparer headers et body".to_string()));
+        }
+    };
+
+    let headers = String::from_utf8_lossy(&response[..header_end]).to_string();
+    let body_start = header_end + separator_len;
+
+    let status = StatusLine::parse(headers.lines().next().unwrap_or(""))?;
+
+    // Le codage peut être réparti sur plusieurs lignes `Transfer-Encoding`
+    let is_chunked = extract_headers(&headers, "Transfer-Encoding")
+        .iter()
+        .any(|te| te.to_lowercase().contains("chunked"));
+
+    let body = if status.has_no_body() {
+        // 1xx, 204 et 304 n'ont jamais de body, quels que soient les headers
+        Vec::new()
+    } else if is_chunked {
+        // Décoder au fil des lectures jusqu'au chunk terminal
+        let mut decoder = ChunkedDecoder::default();
+        loop {
+            if decoder.decode(&response[body_start..])? {
+                break decoder.body;
+            }
+            if !read_more(&mut response)? {
+                return Err(ScraperError::Parse(
+                    "Body chunked tronqué : connexion fermée avant le chunk final".to_string(),
+                ));
+            }
+        }
+    } else if let Some(length) = extract_header(&headers, "Content-Length") {
+        let length: usize = length
+            .parse()
+            .map_err(|_| ScraperError::Parse(format!("Content-Length invalide : \"{}\"", length)))?;
+        while response.len() - body_start < length {
+            if !read_more(&mut response)? {
+                return Err(ScraperError::Parse(format!(
+                    "Body tronqué : {} octets attendus, {} reçus",
+                    length,
+                    response.len() - body_start
+                )));
+            }
+        }
+        response[body_start..body_start + length].to_vec()
+    } else {
+        // Ni chunked ni Content-Length : le body s'arrête à la fermeture
+        while read_more(&mut response)? {}
+        return Ok(HttpResponse {
+            headers,
+            body: response[body_start..].to_vec(),
+            keep_alive: false,
+        });
+    };
+
+    // La connexion reste utilisable sauf si le serveur annonce sa fermeture
+    // (explicitement, ou implicitement en HTTP/1.0 sans keep-alive)
+    let connection = extract_headers(&headers, "Connection").join(", ").to_lowercase();
+    let keep_alive = if status.version == "HTTP/1.0" {
+        connection.contains("keep-alive")
+    } else {
+        !connection.contains("close")
+    };
+
+    Ok(HttpResponse { headers, body, keep_alive })
+}
+
+/// Décompresse un body HTTP selon la valeur du header `Content-Encoding`
+fn decompress_body(body: &[u8], encoding: &str) -> Result<Vec<u8>, ScraperError> {
+    let mut decoded = Vec::new();
+
+    match encoding.trim().to_lowercase().as_str() {
+        "gzip" | "x-gzip" => {
+            GzDecoder::new(body)
+                .read_to_end(&mut decoded)
+                .map_err(|e| ScraperError::Parse(format!("Décompression gzip impossible: {}", e)))?;
+        }
+        "deflate" => {
+            // "deflate" désigne normalement un flux zlib, mais certains serveurs
+            // envoient du deflate brut : on tente les deux
+            if ZlibDecoder::new(body).read_to_end(&mut decoded).is_err() {
+                decoded.clear();
+                DeflateDecoder::new(body)
+                    .read_to_end(&mut decoded)
+                    .map_err(|e| ScraperError::Parse(format!("Décompression deflate impossible: {}", e)))?;
+            }
+        }
+        "identity" | "" => decoded.extend_from_slice(body),
+        other => return Err(ScraperError::Parse(format!("Content-Encoding non supporté: {}", other))),
+    }
+
+    Ok(decoded)
+}
+
+/// Ouvre une connexion TCP en essayant chaque adresse résolue, avec un délai maximal
+fn connect_with_timeout(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, ScraperError> {
+    let addrs = (unbracketed_host(host), port)
+        .to_socket_addrs()
+        .map_err(|e| ScraperError::Connection(format!("Connexion impossible à {}: {}", host, e)))?;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(sock) => return Ok(sock),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    let message = match last_error {
+        Some(e) if e.kind() == io::ErrorKind::TimedOut => format!(
+            "Délai de connexion dépassé ({}s) pour {}",
+            timeout.as_secs(),
+            host
+        ),
+        Some(e) => format!("Connexion impossible à {}: {}", host, e),
+        None => format!("Connexion impossible à {}: aucune adresse trouvée", host),
+    };
+    Err(ScraperError::Connection(message))
+}
+
+/// Recherche la première occurrence d'une séquence d'octets
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Décodeur incrémental d'un body HTTP encodé en `Transfer-Encoding: chunked`
+///
+/// Chaque chunk est précédé de sa taille en hexadécimal (éventuellement suivie
+/// d'extensions `;nom=valeur`). Le chunk de taille 0 termine le body ; les
+/// trailers éventuels qui le suivent sont ignorés.
+///
+/// Les chunks complets sont décodés une seule fois : entre deux lectures sur le
+/// socket, le décodage reprend au premier chunk incomplet.
+#[derive(Default)]
+struct ChunkedDecoder {
+    /// Position, dans le body brut, du premier chunk pas encore décodé
+    pos: usize,
+    /// Contenu des chunks déjà décodés
+    body: Vec<u8>,
+}
+
+impl ChunkedDecoder {
+    /// Décode les chunks devenus complets dans `raw` (le body brut reçu jusqu'ici)
+    ///
+    /// Renvoie `Ok(true)` une fois le chunk terminal et ses trailers reçus, le body
+    /// étant alors dans `self.body`, et `Ok(false)` tant qu'il manque des données.
+    fn decode(&mut self, raw: &[u8]) -> Result<bool, ScraperError> {
+        loop {
+            let mut pos = self.pos;
+
+            // Lire la ligne contenant la taille du chunk
+            let Some(line_end) = find_bytes(&raw[pos..], b"\r\n") else {
+                return Ok(false);
+            };
+            let size_line = String::from_utf8_lossy(&raw[pos..pos + line_end]).to_string();
+            let size_str = size_line.split(';').next().unwrap_or("").trim();
+            let size = usize::from_str_radix(size_str, 16)
+                .map_err(|_| ScraperError::Parse(format!("Taille de chunk invalide : \"{}\"", size_line.trim())))?;
+            pos += line_end + 2;
+
+            // Chunk terminal : consommer les trailers jusqu'à la ligne vide
+            if size == 0 {
+                loop {
+                    let Some(trailer_end) = find_bytes(&raw[pos..], b"\r\n") else {
+                        return Ok(false);
+                    };
+                    pos += trailer_end + 2;
+                    if trailer_end == 0 {
+                        self.pos = pos;
+                        return Ok(true);
+                    }
+                }
+            }
+
+            // Chunk + CRLF final pas encore entièrement reçus
+            if raw.len() < pos + size + 2 {
+                return Ok(false);
+            }
+
+            self.body.extend_from_slice(&raw[pos..pos + size]);
+            pos += size;
+
+            // Chaque chunk se termine par CRLF
+            if !raw[pos..].starts_with(b"\r\n") {
+                return Err(ScraperError::Parse(
+                    "Body chunked invalide : CRLF manquant après un chunk".to_string(),
+                ));
+            }
+            self.pos = pos + 2;
+        }
+    }
+}
+
+/// Valeurs de toutes les occurrences d'un header (`Set-Cookie` répété...), dans
+/// l'ordre de la réponse
+///
+/// Le nom est comparé sans tenir compte de la casse et les espaces autour du `:`
+/// sont ignorés (`Location : /wiki/Foo`). La ligne de statut n'a pas de nom de
+/// header valide et n'est jamais retenue.
+fn extract_headers(response: &str, header_name: &str) -> Vec<String> {
+    response
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case(header_name))
+        .map(|(_, value)| value.trim().to_string())
+        .collect()
+}
+
+/// Valeur de la première occurrence d'un header (voir [`extract_headers`])
+fn extract_header(response: &str, header_name: &str) -> Option<String> {
+    extract_headers(response, header_name).into_iter().next()
+}
+
+/// Résout la valeur d'un header `Location` par rapport à l'URL courante
+///
+/// MediaWiki renvoie parfois un chemin relatif (`/wiki/Foo`) sans schéma ni hôte.
+fn resolve_location(current: &UrlParts, location: &str) -> Result<UrlParts, ScraperError> {
+    let location = location.trim();
+
+    if location.starts_with("http://") || location.starts_with("https://") {
+        return parse_url(location);
+    }
+
+    // URL relative au schéma : //hote/chemin
+    if let Some(rest) = location.strip_prefix("//") {
+        let prefix = match current.scheme {
+            Scheme::Http => "http://",
+            Scheme::Https => "https://",
+        };
+        return parse_url(&format!("{}{}", prefix, rest));
+    }
+
+    // Chemin absolu sur le même hôte, ou chemin relatif au dossier courant
+    let path = if location.starts_with('/') {
+        location.to_string()
+    } else {
+        let base = current.path.split('?').next().unwrap_or("/");
+        let dir = &base[..base.rfind('/').map(|pos| pos + 1).unwrap_or(0)];
+        format!("{}{}", if dir.is_empty() { "/" } else { dir }, location)
+    };
+
+    Ok(UrlParts {
+        path,
+        ..current.clone()
+    })
+}
+
+/// Découpe une URL en schéma, hôte, port et chemin
+///
+/// Le port par défaut dépend du schéma : 443 pour `https://` (ou sans schéma),
+/// 80 pour `http://`.
+pub fn parse_url(url: &str) -> Result<UrlParts, ScraperError> {
+    let url = url.trim();
+
+    let (rest, scheme) = if let Some(rest) = url.strip_prefix("https://") {
+        (rest, Scheme::Https)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        (rest, Scheme::Http)
+    } else {
+        (url, Scheme::Https)
+    };
+
+    let (authority, path) = match rest.find('/') {
+        Some(pos) => (&rest[..pos], rest[pos..].to_string()),
+        None => (rest, "/".to_string()),
+    };
+
+    // Une adresse IPv6 littérale garde ses crochets (`[::1]`) : ils séparent ses `:` de
+    // ceux du port et sont attendus tels quels dans le header Host
+    let (host, port_str) = if authority.starts_with('[') {
+        let end = authority
+            .find(']')
+            .ok_or_else(|| ScraperError::Parse(format!("Adresse IPv6 sans crochet fermant dans l'URL \"{}\"", url)))?;
+        let after = &authority[end + 1..];
+        let port_str = match after.strip_prefix(':') {
+            Some(port_str) => Some(port_str),
+            None if after.is_empty() => None,
+            None => {
+                return Err(ScraperError::Parse(format!(
+                    "Caractères inattendus après l'adresse IPv6 dans l'URL \"{}\": \"{}\"",
+                    url, after
+                )))
+            }
+        };
+        (&authority[..=end], port_str)
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port_str)) => (host, Some(port_str)),
+            None => (authority, None),
+        }
+    };
+    let port = match port_str {
+        Some(port_str) => port_str
+            .parse::<u16>()
+            .map_err(|_| ScraperError::Parse(format!("Port invalide dans l'URL \"{}\": \"{}\"", url, port_str)))?,
+        None => scheme.default_port(),
+    };
+    let host = host.to_string();
+
+    if host.is_empty() || host == "[]" {
+        return Err(ScraperError::Parse(format!("Hôte manquant dans l'URL \"{}\"", url)));
+    }
+
+    Ok(UrlParts { scheme, host, port, path })
+}
+
+/// Télécharge les images dans le dossier donné, nommées d'après le dernier segment
+/// de leur URL ; une image en échec est signalée puis ignorée
+pub fn download_images(images: &[String], folder: &str, config: &HttpConfig) -> Result<(), ScraperError> {
+    if images.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(folder)?;
+
+    let mut downloaded = 0;
+    for image_url in images {
+        let file_name = sanitize(image_url.rsplit('/').next().unwrap_or(""));
+        if file_name.is_empty() {
+            warn!("  ⚠ Image ignorée (nom de fichier introuvable) : {}", image_url);
+            continue;
+        }
+
+        let result = parse_url(image_url).and_then(|target| http_get_bytes(&target, config));
+        match result {
+            Ok(fetched) => {
+                fs::write(format!("{}/{}", folder, file_name), fetched.bytes)?;
+                downloaded += 1;
+            }
+            Err(e) => warn!("  ⚠ Échec du téléchargement de {} : {}", image_url, e),
+        }
+    }
+
+    info!("  ✓ Images téléchargées: {}/{}", downloaded, images.len());
+    Ok(())
+}
+
+/// Format de sortie sélectionnable avec `--format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    /// `data.json` : toutes les données structurées
+    Json,
+    /// `article.md` : article formaté en Markdown
+    Markdown,
+    /// `resume.txt`, `sections.txt`, `liens.txt`... : listes en texte brut
+    Txt,
+    /// `articles.csv` : une ligne de métadonnées par article, pour tout le lot
+    Csv,
+    /// `article.html` : page HTML autonome
+    Html,
+    /// `data.yaml` : toutes les données structurées en YAML
+    Yaml,
+    /// `articles.ndjson` : un objet JSON par ligne, écrit au fil de l'eau
+    Ndjson,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "txt" => Ok(OutputFormat::Txt),
+            "csv" => Ok(OutputFormat::Csv),
+            "html" => Ok(OutputFormat::Html),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            "ndjson" => Ok(OutputFormat::Ndjson),
+            _ => Err(format!(
+                "format inconnu \"{}\" (attendu : json, markdown, txt, csv, html, yaml ou ndjson)",
+                s
+            )),
+        }
+    }
+}
+
+/// Longueur maximale (en octets) d'un nom d'article sur disque : sous la limite
+/// de 255 octets des systèmes de fichiers courants, avec de la marge pour
+/// l'extension et le suffixe de dédoublonnage (`_2`)
+const MAX_FILE_NAME_BYTES: usize = 200;
+
+/// Noms de périphériques réservés par Windows, interdits même suivis d'une extension
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Nom de fichier (ou de dossier) sûr pour un article : nettoyé par `sanitize`,
+/// limité à [`MAX_FILE_NAME_BYTES`] octets et sans points ni espaces finaux
+///
+/// Un nom vide, fait uniquement de points ou réservé par Windows (`CON`, `NUL.md`...)
+/// est remplacé par `article_<index>`.
+pub fn safe_file_name(name: &str, index: usize) -> String {
+    let mut name = sanitize(name);
+    if name.len() > MAX_FILE_NAME_BYTES {
+        let mut end = MAX_FILE_NAME_BYTES;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+    let name = name.trim_end_matches(['.', ' ']);
+
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let reserved = WINDOWS_RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem));
+    if name.is_empty() || reserved {
+        format!("article_{}", index)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Emplacement des fichiers d'un article
+#[derive(Debug, Clone, Copy)]
+pub enum PageLocation<'a> {
+    /// Un dossier par article : `data.json`, `article.md`, `resume.txt`...
+    Folder(&'a str),
+    /// Fichiers à plat partageant un même chemin sans extension : `Titre.json`, `Titre.md`...
+    Flat(&'a str),
+}
+
+impl PageLocation<'_> {
+    /// Chemin d'un fichier : `folder_name` dans un dossier, sinon l'extension `ext`
+    pub fn file(&self, folder_name: &str, ext: &str) -> String {
+        match self {
+            PageLocation::Folder(folder) => format!("{}/{}", folder, folder_name),
+            PageLocation::Flat(stem) => format!("{}.{}", stem, ext),
+        }
+    }
+
+    /// Indique si l'un des fichiers des formats demandés existe déjà
+    pub fn exists(&self, formats: &[OutputFormat]) -> bool {
+        formats.iter().filter_map(|format| match format {
+            OutputFormat::Json => Some(self.file("data.json", "json")),
+            OutputFormat::Markdown => Some(self.file("article.md", "md")),
+            OutputFormat::Txt => Some(self.file("resume.txt", "txt")),
+            OutputFormat::Html => Some(self.file("article.html", "html")),
+            OutputFormat::Yaml => Some(self.file("data.yaml", "yaml")),
+            OutputFormat::Csv | OutputFormat::Ndjson => None,
+        })
+        .any(|path| Path::new(&path).exists() || Path::new(&format!("{}.gz", path)).exists())
+    }
+}
+
+/// Fonction pour sauvegarder les données d'une page dans les formats demandés
+///
+/// `Csv` et `Ndjson` concernent tout le lot et sont ignorés ici. À plat, le
+/// format `Txt` se limite au fichier de résumé. Le texte intégral (`--full-text`)
+/// est écrit dans `texte_complet.txt` quel que soit le format. Avec `bom`, les
+/// fichiers `.txt` commencent par un BOM UTF-8, pour Excel sous Windows.
+/// Avec `gzip`, chaque fichier est compressé et prend l'extension `.gz`
+/// (`data.json.gz`, `article.md.gz`...).
+pub fn save_page_data(
+    page: &WikipediaPage,
+    location: PageLocation,
+    formats: &[OutputFormat],
+    markdown_options: &MarkdownOptions,
+    bom: bool,
+    gzip: bool,
+) -> Result<(), ScraperError> {
+    for format in formats {
+        match format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(page)?;
+                write_output_file(&location.file("data.json", "json"), json.as_bytes(), gzip)?;
+            }
+            OutputFormat::Markdown => {
+                let markdown_content = generate_markdown(page, markdown_options);
+                write_output_file(&location.file("article.md", "md"), markdown_content.as_bytes(), gzip)?;
+            }
+            OutputFormat::Txt => save_page_txt(page, location, bom, gzip)?,
+            OutputFormat::Html => {
+                write_output_file(&location.file("article.html", "html"), generate_html(page).as_bytes(), gzip)?
+            }
+            OutputFormat::Yaml => {
+                let yaml = serde_yaml::to_string(page)?;
+                write_output_file(&location.file("data.yaml", "yaml"), yaml.as_bytes(), gzip)?;
+            }
+            OutputFormat::Csv | OutputFormat::Ndjson => {}
+        }
+    }
+    if !page.full_text.is_empty() {
+        let full_text_path = location.file("texte_complet.txt", "texte_complet.txt");
+        write_text_file(&full_text_path, &format!("{}\n", page.full_text), bom, gzip)?;
+    }
+    Ok(())
+}
+
+/// Écrit un fichier de sortie, ou sa version compressée `<path>.gz` si `gzip` est vrai
+fn write_output_file(path: &str, content: &[u8], gzip: bool) -> io::Result<()> {
+    if !gzip {
+        return fs::write(path, content);
+    }
+    let file = fs::File::create(format!("{}.gz", path))?;
+    let mut encoder = GzEncoder::new(io::BufWriter::new(file), Compression::default());
+    encoder.write_all(content)?;
+    encoder.finish()?.flush()
+}
+
+/// Lit un fichier texte écrit par [`save_page_data`], compressé (`<path>.gz`) ou non
+pub fn read_output_file(path: &str) -> io::Result<String> {
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound && Path::new(&format!("{}.gz", path)).exists() => {
+            read_gzip_file(&format!("{}.gz", path))
+        }
+        result => result,
+    }
+}
+
+/// Lit un fichier texte compressé en gzip
+pub fn read_gzip_file(path: &str) -> io::Result<String> {
+    let mut content = String::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Écrit un fichier texte, précédé d'un BOM UTF-8 si `bom` est vrai : sans lui,
+/// Excel sous Windows lit le fichier en ANSI et abîme les caractères accentués.
+/// Compressé en `<path>.gz` si `gzip` est vrai.
+fn write_text_file(path: &str, content: &str, bom: bool, gzip: bool) -> io::Result<()> {
+    if bom {
+        write_output_file(path, format!("\u{FEFF}{}", content).as_bytes(), gzip)
+    } else {
+        write_output_file(path, content.as_bytes(), gzip)
+    }
+}
+
+/// Écrit les fichiers texte d'un article (résumé, puis listes en mode dossier)
+fn save_page_txt(page: &WikipediaPage, location: PageLocation, bom: bool, gzip: bool) -> Result<(), ScraperError> {
+    let summary_content = format!(
+        "Titre: {}\n\nURL: {}\n\nRésumé:\n{}\n",
+        page.title, page.url, page.summary
+    );
+    write_text_file(&location.file("resume.txt", "txt"), &summary_content, bom, gzip)?;
+
+    let PageLocation::Folder(folder) = location else {
+        return Ok(());
+    };
+
+    let sections_path = format!("{}/sections.txt", folder);
+    let sections_content = page
+        .sections
+        .iter()
+        .map(|section| section.title.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    write_text_file(&sections_path, &sections_content, bom, gzip)?;
+
+    let links_path = format!("{}/liens.txt", folder);
+    let links_content = page.links.join("\n");
+    write_text_file(&links_path, &links_content, bom, gzip)?;
+
+    let external_links_path = format!("{}/liens_externes.txt", folder);
+    write_text_file(&external_links_path, &page.external_links.join("\n"), bom, gzip)?;
+
+    let images_path = format!("{}/images.txt", folder);
+    let images_content = page.images.iter().map(|image| image.url.as_str()).collect::<Vec<_>>().join("\n");
+    write_text_file(&images_path, &images_content, bom, gzip)?;
+
+    let categories_path = format!("{}/categories.txt", folder);
+    let categories_content = page.categories.join("\n");
+    write_text_file(&categories_path, &categories_content, bom, gzip)?;
+
+    let references_path = format!("{}/references.txt", folder);
+    let references_content = page.references.join("\n");
+    write_text_file(&references_path, &references_content, bom, gzip)?;
+
+    Ok(())
+}
+
+/// Markdown d'une seule section d'un article (`--section`) : titre de l'article,
+/// source, puis le titre et le texte de la section
+pub fn generate_section_markdown(page: &WikipediaPage, section: &Section) -> String {
+    let mut markdown = format!("# {}\n\n", page.title);
+    markdown.push_str(&format!("**Source:** [Wikipedia]({})  \n\n", page.url));
+    markdown.push_str(&format!("## {}\n\n", section.title));
+    if section.body.is_empty() {
+        markdown.push_str("*Section sans texte*\n");
+    } else {
+        markdown.push_str(&section.body);
+        markdown.push('\n');
+    }
+    markdown
+}
+
+/// Écrit `section.md` et `section.txt` (à plat : `Titre.section.md`...), qui ne
+/// contiennent que la section demandée par `--section`
+pub fn save_section(
+    page: &WikipediaPage,
+    section: &Section,
+    location: PageLocation,
+    bom: bool,
+    gzip: bool,
+) -> Result<(), ScraperError> {
+    let markdown = generate_section_markdown(page, section);
+    write_output_file(&location.file("section.md", "section.md"), markdown.as_bytes(), gzip)?;
+
+    let text = format!(
+        "Titre: {}\n\nURL: {}\n\nSection: {}\n{}\n",
+        page.title, page.url, section.title, section.body
+    );
+    write_text_file(&location.file("section.txt", "section.txt"), &text, bom, gzip)?;
+    Ok(())
+}
+
+/// Contenu facultatif du Markdown produit par `generate_markdown`
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownOptions {
+    /// Liste « Voir aussi » des liens internes extraits
+    pub include_links: bool,
+    /// Galerie des images de l'article
+    pub include_images: bool,
+}
+
+/// Liens et images inclus, comme dans la ligne de commande par défaut
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        MarkdownOptions {
+            include_links: true,
+            include_images: true,
+        }
+    }
+}
+
+/// Rend un tableau en Markdown (GFM) ; la première ligne sert d'en-tête et les
+/// lignes plus courtes sont complétées par des cellules vides
+fn markdown_table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let render_row = |row: &[String]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| row.get(i).map(|cell| cell.replace('|', "\\|")).unwrap_or_default())
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut table = String::new();
+    if let Some((header, body)) = rows.split_first() {
+        table.push_str(&render_row(header));
+        table.push_str(&format!("|{}\n", "---|".repeat(columns)));
+        for row in body {
+            table.push_str(&render_row(row));
+        }
+    }
+    table
+}
+
+/// Libellé lisible d'un lien interne : le titre tiré du chemin `/wiki/...`
+fn link_label(url: &str) -> String {
+    match url.split_once("/wiki/") {
+        Some((_, title)) if !title.is_empty() => title.replace('_', " "),
+        _ => url.to_string(),
+    }
+}
+
+pub fn generate_markdown(page: &WikipediaPage, options: &MarkdownOptions) -> String {
+    let mut markdown = String::new();
+    
+    markdown.push_str(&format!("# {}\n\n", page.title));
+    if let Some(lead_image) = &page.lead_image {
+        markdown.push_str(&format!("![{}](<{}>)\n\n", page.title, lead_image));
+    }
+    markdown.push_str(&format!("**Source:** [Wikipedia]({})  \n", page.url));
+    markdown.push_str(&format!("**Date:** {}  \n\n", 
+        chrono::Local::now().format("%d/%m/%Y à %H:%M:%S")));
+    
+    markdown.push_str(&format!(
+        "**Longueur:** {} mots (~{} min de lecture)  \n\n",
+        page.word_count, page.reading_time_minutes
+    ));
+    
+    if page.crawl_depth > 0 {
+        markdown.push_str(&format!("**Profondeur d'exploration:** {}  \n\n", page.crawl_depth));
+    }
+    
+    if let Some((lat, lon)) = page.coordinates {
+        markdown.push_str(&format!(
+            "**Coordonnées:** [{lat}, {lon}](https://www.openstreetmap.org/?mlat={lat}&mlon={lon}#map=12/{lat}/{lon})  \n\n"
+        ));
+    }
+    
+    markdown.push_str("## Résumé\n\n");
+    if !page.summary.is_empty() {
+        markdown.push_str(&page.summary);
+        markdown.push_str("\n\n");
+    } else {
+        markdown.push_str("*Résumé non disponible*\n\n");
+    }
+    
+    if !page.infobox.is_empty() {
+        markdown.push_str("## Infobox\n\n");
+        markdown.push_str("| Champ | Valeur |\n");
+        markdown.push_str("|-------|--------|\n");
+        for (label, value) in &page.infobox {
+            markdown.push_str(&format!(
+                "| {} | {} |\n",
+                label.replace('|', "\\|"),
+                value.replace('|', "\\|")
+            ));
+        }
+        markdown.push('\n');
+    }
+    
+    // Les sections gardent leur niveau d'origine (## pour h2, ### pour h3...)
+    for section in &page.sections {
+        let hashes = "#".repeat(section.level.clamp(2, 6) as usize);
+        markdown.push_str(&format!("{} {}\n\n", hashes, section.title));
+        if !section.body.is_empty() {
+            markdown.push_str(&section.body);
+            markdown.push_str("\n\n");
+        }
+    }
+    
+    if !page.tables.is_empty() {
+        markdown.push_str("## Tableaux\n\n");
+        for table in &page.tables {
+            markdown.push_str(&markdown_table(table));
+            markdown.push('\n');
+        }
+    }
+    
+    if options.include_images && !page.images.is_empty() {
+        markdown.push_str("## Images\n\n");
+        for image in &page.images {
+            let file_name = image.url.rsplit('/').next().unwrap_or(&image.url);
+            let label = if image.alt.is_empty() { file_name } else { &image.alt };
+            markdown.push_str(&format!("![{}](<{}>)\n", label, image.url));
+            if !image.caption.is_empty() {
+                markdown.push_str(&format!("*{}*\n", image.caption));
+            }
+            markdown.push('\n');
+        }
+    }
+    
+    if options.include_links && !page.links.is_empty() {
+        markdown.push_str("## Voir aussi\n\n");
+        for link in &page.links {
+            markdown.push_str(&format!("- [{}](<{}>)\n", link_label(link), link));
+        }
+        markdown.push('\n');
+    }
+    
+    if !page.categories.is_empty() {
+        markdown.push_str("## Catégories\n\n");
+        for category in &page.categories {
+            markdown.push_str(&format!("- {}\n", category));
+        }
+        markdown.push('\n');
+    }
+    
+    if !page.languages.is_empty() {
+        markdown.push_str("## Autres langues\n\n");
+        for (code, url) in &page.languages {
+            markdown.push_str(&format!("- {} : [{}](<{}>)\n", code, link_label(url), url));
+        }
+        markdown.push('\n');
+    }
+    
+    if !page.disambiguation_options.is_empty() {
+        markdown.push_str("## Articles homonymes\n\n");
+        for option in &page.disambiguation_options {
+            markdown.push_str(&format!("- <{}>\n", option));
+        }
+        markdown.push('\n');
+    }
+    
+    markdown
+}
+
+/// Feuille de style intégrée aux pages générées par `generate_html`
+const HTML_STYLE: &str = "body{font-family:Georgia,serif;max-width:50em;margin:2em auto;padding:0 1em;line-height:1.6;color:#202122}\
+h1,h2,h3,h4,h5,h6{font-family:sans-serif;border-bottom:1px solid #eaecf0}\
+.meta{color:#54595d;font-size:.9em}\
+.lead{float:right;max-width:40%;margin:0 0 1em 1em}\
+table{border-collapse:collapse;margin:1em 0}th,td{border:1px solid #a2a9b1;padding:.2em .5em}\
+.gallery{display:flex;flex-wrap:wrap;gap:.5em}.gallery img{height:10em}";
+
+/// Échappe un texte pour le contenu d'une balise HTML
+fn html_text(text: &str) -> String {
+    html_escape::encode_text(text).to_string()
+}
+
+/// URL utilisable dans un attribut `href`/`src` : seuls http(s) sont acceptés,
+/// pour qu'un lien `javascript:` venu du contenu ne soit jamais actif
+fn html_url(url: &str) -> Option<String> {
+    let lower = url.to_ascii_lowercase();
+    (lower.starts_with("https://") || lower.starts_with("http://"))
+        .then(|| html_escape::encode_double_quoted_attribute(url).to_string())
+}
+
+/// Génère une page HTML autonome (styles intégrés) : titre, résumé, infobox,
+/// sections et galerie d'images ; tout le texte de l'article est échappé
+pub fn generate_html(page: &WikipediaPage) -> String {
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", html_text(&page.title)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_STYLE));
+
+    html.push_str(&format!("<h1>{}</h1>\n", html_text(&page.title)));
+    let source = match html_url(&page.url) {
+        Some(url) => format!("<a href=\"{}\">Wikipedia</a>", url),
+        None => html_text(&page.url),
+    };
+    html.push_str(&format!(
+        "<p class=\"meta\">Source : {} — {} mots (~{} min de lecture)</p>\n",
+        source, page.word_count, page.reading_time_minutes
+    ));
+
+    if let Some(lead_image) = page.lead_image.as_deref().and_then(html_url) {
+        html.push_str(&format!(
+            "<img class=\"lead\" src=\"{}\" alt=\"{}\">\n",
+            lead_image,
+            html_escape::encode_double_quoted_attribute(&page.title)
+        ));
+    }
+
+    if page.summary.is_empty() {
+        html.push_str("<p><em>Résumé non disponible</em></p>\n");
+    } else {
+        for paragraph in page.summary.split("\n\n") {
+            html.push_str(&format!("<p>{}</p>\n", html_text(paragraph)));
+        }
+    }
+
+    if !page.infobox.is_empty() {
+        html.push_str("<table>\n");
+        for (label, value) in &page.infobox {
+            html.push_str(&format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                html_text(label),
+                html_text(value)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+
+    for section in &page.sections {
+        let level = section.level.clamp(2, 6);
+        html.push_str(&format!("<h{level}>{}</h{level}>\n", html_text(&section.title)));
+        for paragraph in section.body.split("\n\n").filter(|p| !p.is_empty()) {
+            html.push_str(&format!("<p>{}</p>\n", html_text(paragraph)));
+        }
+    }
+
+    let images: Vec<(String, &Image)> =
+        page.images.iter().filter_map(|image| Some((html_url(&image.url)?, image))).collect();
+    if !images.is_empty() {
+        html.push_str("<h2>Images</h2>\n<div class=\"gallery\">\n");
+        for (url, image) in images {
+            html.push_str(&format!(
+                "<a href=\"{0}\" title=\"{2}\"><img src=\"{0}\" alt=\"{1}\" loading=\"lazy\"></a>\n",
+                url,
+                html_escape::encode_double_quoted_attribute(&image.alt),
+                html_escape::encode_double_quoted_attribute(&image.caption)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Entrée du cache disque : le HTML téléchargé et ses validateurs HTTP
+///
+/// C'est le HTML brut qui est conservé, et non la page extraite : sur un 304, il
+/// est ré-analysé avec les options de l'exécution en cours (`-k`, `--max-links`,
+/// `--full-text`...), qui peuvent différer de celles du premier scraping.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    /// Date du scraping (RFC 3339)
+    pub fetched_at: String,
+    /// URL finale après redirections, qui identifie l'article
+    pub final_url: String,
+    pub validators: Validators,
+    pub html: String,
+}
+
+/// Cache disque des pages déjà scrapées, un fichier JSON par URL canonique
+pub struct PageCache {
+    dir: PathBuf,
+}
+
+impl PageCache {
+    /// Ouvre le cache dans `dir`, en créant le dossier au besoin
+    pub fn new(dir: &str) -> Result<Self, ScraperError> {
+        fs::create_dir_all(dir)?;
+        Ok(PageCache { dir: PathBuf::from(dir) })
+    }
+
+    /// Renvoie l'entrée en cache pour cette URL ; un fichier illisible (ou écrit par
+    /// une version qui stockait la page extraite) compte comme absent
+    pub fn get(&self, url: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.entry_path(url)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Enregistre (ou remplace) l'entrée d'une URL
+    pub fn put(&self, url: &str, fetched: &FetchedPage) -> Result<(), ScraperError> {
+        let entry = CacheEntry {
+            url: canonical_url(url),
+            fetched_at: chrono::Local::now().to_rfc3339(),
+            final_url: fetched.final_url.clone(),
+            validators: fetched.validators.clone(),
+            html: fetched.html.clone(),
+        };
+        fs::write(self.entry_path(url), serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    /// Nom de fichier stable dérivé de l'URL canonique (hachage FNV-1a 64 bits)
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let hash = canonical_url(url)
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        self.dir.join(format!("{:016x}.json", hash))
+    }
+}
+
+/// Ouvre (ou crée) la base SQLite et s'assure que le schéma existe
+pub fn open_database(path: &str) -> Result<Connection, ScraperError> {
+    let conn = Connection::open(path)?;
+
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         CREATE TABLE IF NOT EXISTS pages (
+             id         INTEGER PRIMARY KEY,
+             url        TEXT NOT NULL UNIQUE,
+             title      TEXT NOT NULL,
+             summary    TEXT NOT NULL,
+             scraped_at TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS sections (
+             page_id  INTEGER NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+             position INTEGER NOT NULL,
+             title    TEXT NOT NULL,
+             level    INTEGER NOT NULL,
+             body     TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS links (
+             page_id  INTEGER NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+             position INTEGER NOT NULL,
+             url      TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS images (
+             page_id  INTEGER NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+             position INTEGER NOT NULL,
+             url      TEXT NOT NULL
+         );",
+    )?;
+
+    Ok(conn)
+}
+
+/// Enregistre une page dans la base ; une page déjà connue (même URL) est mise à jour
+/// et ses sections, liens et images sont remplacés
+pub fn save_page_to_db(conn: &Connection, page: &WikipediaPage) -> Result<(), ScraperError> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "INSERT INTO pages (url, title, summary, scraped_at) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(url) DO UPDATE SET
+             title = excluded.title,
+             summary = excluded.summary,
+             scraped_at = excluded.scraped_at",
+        params![page.url, page.title, page.summary, chrono::Local::now().to_rfc3339()],
+    )?;
+    let page_id: i64 = tx.query_row("SELECT id FROM pages WHERE url = ?1", params![page.url], |row| row.get(0))?;
+
+    for table in ["sections", "links", "images"] {
+        tx.execute(&format!("DELETE FROM {} WHERE page_id = ?1", table), params![page_id])?;
+    }
+
+    for (position, section) in page.sections.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO sections (page_id, position, title, level, body) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![page_id, position, section.title, section.level, section.body],
+        )?;
+    }
+    for (position, link) in page.links.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO links (page_id, position, url) VALUES (?1, ?2, ?3)",
+            params![page_id, position, link],
+        )?;
+    }
+    for (position, image) in page.images.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO images (page_id, position, url) VALUES (?1, ?2, ?3)",
+            params![page_id, position, image.url],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+/// Écrit `manifest.json` : index stable de l'exécution pour l'automatisation
+/// (terme recherché, date, dossier, et pour chaque article son titre, son URL,
+/// l'URL demandée avant redirections, son chemin relatif au dossier et ses compteurs)
+///
+/// `saved_paths` et `requested_urls` suivent l'ordre de `articles`.
+pub fn write_manifest(
+    articles: &[WikipediaPage],
+    saved_paths: &[String],
+    requested_urls: &[String],
+    folder: &str,
+    search_term: Option<&str>,
+) -> Result<(), ScraperError> {
+    let manifest_path = format!("{}/manifest.json", folder);
+
+    let entries: Vec<serde_json::Value> = articles
+        .iter()
+        .zip(saved_paths)
+        .zip(requested_urls)
+        .map(|((article, path), requested_url)| {
+            let relative_path = relative_saved_path(path, folder);
+            serde_json::json!({
+                "title": article.title,
+                "url": article.url,
+                "requested_url": requested_url,
+                "path": relative_path,
+                "sections": article.sections.len(),
+                "links": article.links.len(),
+                "images": article.images.len(),
+                "categories": article.categories.len(),
+                "references": article.references.len(),
+            })
+        })
+        .collect();
+
+    let manifest = serde_json::json!({
+        "search_term": search_term,
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "output_folder": folder,
+        "article_count": articles.len(),
+        "articles": entries,
+    });
+
+    fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+    info!("📄 Manifeste généré : {}", manifest_path);
+
+    Ok(())
+}
+
+/// Écrit tous les articles du lot dans un unique tableau JSON (`all.json`)
+pub fn write_all_json(articles: &[WikipediaPage], folder: &str) -> Result<(), ScraperError> {
+    let json_path = format!("{}/all.json", folder);
+    let json = serde_json::to_string_pretty(articles)?;
+    fs::write(&json_path, json)?;
+    info!("📄 Export JSON groupé généré : {}", json_path);
+
+    Ok(())
+}
+
+/// Article sauvegardé par une exécution précédente, retrouvé par [`resume_index`]
+#[derive(Debug, Clone)]
+pub struct SavedArticle {
+    /// Chemin de l'article sans extension : son dossier, ou le préfixe de ses fichiers à plat
+    pub base: String,
+    /// Données de l'article, absentes si seuls des fichiers sans elles (Markdown, texte)
+    /// ont été écrits avant une interruption
+    pub page: Option<WikipediaPage>,
+}
+
+/// Articles sauvegardés dans `folder` par une exécution précédente (`--resume`),
+/// indexés par URL canonique
+///
+/// Les chemins viennent du `manifest.json` (URL de l'article et URL demandée, qui
+/// diffèrent après une redirection) et les données de `all.json`, tous deux écrits en
+/// fin d'exécution quel que soit `--format`. Une exécution interrompue avant ne les a
+/// pas écrits : chaque article est alors retrouvé par ses propres fichiers,
+/// `data.json` ou `data.yaml`, ou à défaut la ligne `**Source:**` de son Markdown,
+/// qui ne donne que son URL.
+pub fn resume_index(folder: &str, keyword_mode: bool) -> HashMap<String, SavedArticle> {
+    let all_pages: Vec<WikipediaPage> = read_output_file(&format!("{}/all.json", folder))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let mut pages: HashMap<String, WikipediaPage> =
+        all_pages.into_iter().map(|page| (canonical_url(&page.url), page)).collect();
+    let mut index = HashMap::new();
+
+    let manifest: Option<serde_json::Value> = fs::read_to_string(format!("{}/manifest.json", folder))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok());
+    let entries = manifest.as_ref().and_then(|manifest| manifest["articles"].as_array());
+    for entry in entries.into_iter().flatten() {
+        let (Some(path), Some(url)) = (entry["path"].as_str(), entry["url"].as_str()) else {
+            continue;
+        };
+        let base = format!("{}/{}", folder, path.strip_suffix(".md").unwrap_or(path));
+        let page = pages
+            .remove(&canonical_url(url))
+            .or_else(|| read_saved_page(&saved_location(&base, keyword_mode)));
+        let saved = SavedArticle { base, page };
+        for url in [&entry["url"], &entry["requested_url"]].into_iter().filter_map(|url| url.as_str()) {
+            index.insert(canonical_url(url), saved.clone());
+        }
+    }
+
+    let Ok(dir) = fs::read_dir(folder) else {
+        return index;
+    };
+    let mut bases: Vec<String> = dir
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path().to_string_lossy().into_owned();
+            if !keyword_mode {
+                return entry.path().is_dir().then_some(path);
+            }
+            let path = path.strip_suffix(".gz").unwrap_or(&path);
+            let stem = [".json", ".yaml", ".md"].iter().find_map(|ext| path.strip_suffix(ext))?;
+            let name = stem.rsplit('/').next().unwrap_or(stem);
+            (!matches!(name, "manifest" | "all" | "RESUME_RECHERCHE")).then(|| stem.to_string())
+        })
+        .collect();
+    bases.sort();
+    bases.dedup();
+
+    for base in bases {
+        let location = saved_location(&base, keyword_mode);
+        let page = read_saved_page(&location);
+        let Some(url) = page.as_ref().map(|page| page.url.clone()).or_else(|| read_saved_url(&location)) else {
+            continue;
+        };
+        let url = canonical_url(&url);
+        let page = page.or_else(|| pages.remove(&url));
+        index.entry(url).or_insert(SavedArticle { base, page });
+    }
+    index
+}
+
+/// Emplacement des fichiers d'un article sauvegardé au chemin `base`
+fn saved_location(base: &str, keyword_mode: bool) -> PageLocation<'_> {
+    if keyword_mode {
+        PageLocation::Flat(base)
+    } else {
+        PageLocation::Folder(base)
+    }
+}
+
+/// Données d'un article relues depuis son `data.json` ou son `data.yaml`
+fn read_saved_page(location: &PageLocation) -> Option<WikipediaPage> {
+    read_output_file(&location.file("data.json", "json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .or_else(|| {
+            read_output_file(&location.file("data.yaml", "yaml"))
+                .ok()
+                .and_then(|yaml| serde_yaml::from_str(&yaml).ok())
+        })
+}
+
+/// URL d'un article relue depuis la ligne `**Source:** [Wikipedia](URL)` de son Markdown
+fn read_saved_url(location: &PageLocation) -> Option<String> {
+    let markdown = read_output_file(&location.file("article.md", "md")).ok()?;
+    markdown.lines().find_map(|line| {
+        let url = line.trim_end().strip_prefix("**Source:** [Wikipedia](")?.strip_suffix(')')?;
+        Some(url.to_string())
+    })
+}
+
+/// Écrit `articles.csv` : une ligne de métadonnées par article scrapé
+/// (précédée d'un BOM UTF-8 avec `bom`)
+pub fn write_csv(articles: &[WikipediaPage], folder: &str, bom: bool) -> Result<(), ScraperError> {
+    let csv_path = format!("{}/articles.csv", folder);
+    let mut csv = String::from("title,url,summary_length,section_count,link_count,image_count\n");
+
+    for article in articles {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&article.title),
+            csv_field(&article.url),
+            article.summary.chars().count(),
+            article.sections.len(),
+            article.links.len(),
+            article.images.len()
+        ));
+    }
+
+    write_text_file(&csv_path, &csv, bom, false)?;
+    info!("📄 Export CSV généré : {}", csv_path);
+
+    Ok(())
+}
+
+/// Met un champ CSV entre guillemets s'il contient une virgule, un guillemet ou
+/// un retour à la ligne (les guillemets internes sont doublés)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Chemin de sauvegarde d'un article, relatif au dossier de recherche
+fn relative_saved_path(path: &str, folder: &str) -> String {
+    Path::new(path)
+        .strip_prefix(folder)
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// Fonction pour générer un résumé de la recherche
+///
+/// `saved_paths` donne, dans le même ordre que `articles`, le chemin où chaque
+/// article a été sauvegardé : les liens du résumé pointent vers ces fichiers.
+pub fn generate_search_summary(
+    articles: &[WikipediaPage], 
+    saved_paths: &[String],
+    folder: &str, 
+    search_term: Option<&str>
+) -> Result<(), ScraperError> {
+    let summary_path = format!("{}/RESUME_RECHERCHE.md", folder);
+    let mut summary = String::new();
+    
+    // En-tête
+    if let Some(term) = search_term {
+        summary.push_str(&format!("# 🔍 Résumé de recherche : \"{}\"\n\n", term));
+    } else {
+        summary.push_str("# 📚 Résumé de scraping\n\n");
+    }
+    
+    summary.push_str(&format!("**Date** : {}\n\n", 
+        chrono::Local::now().format("%d/%m/%Y à %H:%M:%S")));
+    summary.push_str(&format!("**Nombre d'articles** : {}\n\n", articles.len()));
+    
+    summary.push_str("---\n\n");
+    
+    // Table des matières (pas de tableau vide quand aucun article n'a été sauvegardé)
+    summary.push_str("## 📋 Articles scrapés\n\n");
+    if articles.is_empty() {
+        summary.push_str("*Aucun article scrapé.*\n");
+    } else {
+        summary.push_str("| # | Article | Illustration | Sections | Liens | Images | Dossier |\n");
+        summary.push_str("|---|---------|--------------|----------|-------|--------|----------|\n");
+    }
+    
+    for (i, (article, path)) in articles.iter().zip(saved_paths).enumerate() {
+        let table_link = format!("./{}", markdown_path(path, folder, search_term));
+
+        let table_icon = if search_term.is_some() { "📄" } else { "📁" };
+
+        let illustration = match &article.lead_image {
+            Some(url) => format!("[🖼]({})", url),
+            None => "—".to_string(),
+        };
+
+        summary.push_str(&format!(
+            "| {} | [{}]({}) | {} | {} | {} | {} | [{}]({}) |\n",
+            i + 1,
+            article.title,
+            article.url,
+            illustration,
+            article.sections.len(),
+            article.links.len(),
+            article.images.len(),
+            table_icon,
+            table_link
+        ));
+    }
+    
+    summary.push_str("\n---\n\n");
+    
+    // Résumés courts de chaque article
+    summary.push_str("## 📖 Résumés des articles\n\n");
+    
+    for (i, (article, path)) in articles.iter().zip(saved_paths).enumerate() {
+        let relative_path = relative_saved_path(path, folder);
+        summary.push_str(&format!("### {}. {}\n\n", i + 1, article.title));
+        summary.push_str(&format!("**URL** : [{}]({})\n\n", article.title, article.url));
+        
+            if !article.summary.is_empty() {
+                summary.push_str(&format!("{}\n\n", short_summary(article)));
+            // Lien vers le markdown : soit ./<title>.md (mode mot-clé), soit ./<title>/article.md
+            summary.push_str(&format!(
+                "> 📄 [Lire l'article complet](./{})\n\n",
+                markdown_path(path, folder, search_term)
+            ));
+        } else {
+            summary.push_str("*Résumé non disponible*\n\n");
+            if search_term.is_some() {
+                summary.push_str(&format!("> 📄 [Consulter les données](./{})\n\n", markdown_path(path, folder, search_term)));
+            } else {
+                summary.push_str(&format!("> 📄 [Consulter les données](./{}/)\n\n", relative_path));
+            }
+        }
+    
+        // Sections principales
+        if !article.sections.is_empty() {
+            summary.push_str("**Sections principales** : ");
+            let sections_preview: Vec<String> = article.sections.iter().take(5).map(|s| s.title.clone()).collect();
+            summary.push_str(&sections_preview.join(", "));
+            if article.sections.len() > 5 {
+                summary.push_str(&format!(" (et {} autres...)", article.sections.len() - 5));
+            }
+            summary.push_str("\n\n");
+        }
+        
+        summary.push_str("---\n\n");
+    }
+    
+    // Statistiques globales
+    summary.push_str("## 📊 Statistiques globales\n\n");
+    summary.push_str("```\n");
+    summary.push_str(&format!("Total articles       : {}\n", articles.len()));
+    summary.push_str(&format!("Total sections       : {}\n", articles.iter().map(|a| a.sections.len()).sum::<usize>()));
+    summary.push_str(&format!("Total liens          : {}\n", articles.iter().map(|a| a.links.len()).sum::<usize>()));
+    summary.push_str(&format!("Total images         : {}\n", articles.iter().map(|a| a.images.len()).sum::<usize>()));
+    
+    // Pas de moyenne sur une liste vide (division par zéro → NaN)
+    if articles.is_empty() {
+        summary.push_str("Moyenne sections     : N/A\n");
+    } else {
+        let avg_sections = articles.iter().map(|a| a.sections.len()).sum::<usize>() as f64 / articles.len() as f64;
+        summary.push_str(&format!("Moyenne sections     : {:.1}\n", avg_sections));
+    }
+    
+    let total_chars: usize = articles.iter().map(|a| a.summary.len()).sum();
+    summary.push_str(&format!("Total caractères     : {}\n", total_chars));
+    let total_words: usize = articles.iter().map(|a| a.word_count).sum();
+    summary.push_str(&format!("Total mots           : {}\n", total_words));
+    summary.push_str(&format!(
+        "Temps de lecture     : ~{} min\n",
+        articles.iter().map(|a| a.reading_time_minutes).sum::<usize>()
+    ));
+    summary.push_str("```\n\n");
+    
+    // Footer
+    summary.push_str("---\n\n");
+    summary.push_str("*Résumé généré automatiquement par le Scrappeur Wikipedia en Rust*\n");
+    summary.push_str("*ESGI - BAC +4 RUST*\n");
+    
+    fs::write(&summary_path, summary)?;
+    info!("\n📄 Résumé de recherche généré : {}", summary_path);
+    
+    Ok(())
+}
+
+/// Markdown d'un article, relatif au dossier de recherche : fichier à plat
+/// (`Titre.md`) en recherche par mot-clé, `Titre/article.md` sinon
+fn markdown_path(saved_path: &str, folder: &str, search_term: Option<&str>) -> String {
+    let relative_path = relative_saved_path(saved_path, folder);
+    let path = match search_term {
+        Some(_) if relative_path.ends_with(".md") => relative_path,
+        Some(_) => format!("{}.md", relative_path),
+        None => format!("{}/article.md", relative_path),
+    };
+    find_output_file(folder, &path).unwrap_or(path)
+}
+
+/// Fichier `relative_path` du dossier `folder` tel qu'il a été écrit : lui-même,
+/// ou sa version compressée `<relative_path>.gz` (`--gzip-output`)
+fn find_output_file(folder: &str, relative_path: &str) -> Option<String> {
+    let compressed = format!("{}.gz", relative_path);
+    [relative_path.to_string(), compressed]
+        .into_iter()
+        .find(|file| Path::new(folder).join(file).exists())
+}
+
+/// Description courte d'un article : le premier paragraphe ; à défaut (pages en
+/// cache d'une version précédente), les 300 premiers caractères du résumé
+fn short_summary(article: &WikipediaPage) -> String {
+    if !article.lead.is_empty() {
+        article.lead.clone()
+    } else if article.summary.chars().count() > 300 {
+        let mut s: String = article.summary.chars().take(300).collect();
+        s.push_str("...");
+        s
+    } else {
+        article.summary.clone()
+    }
+}
+
+/// Génère `index.html` à la racine du dossier de recherche : version HTML de
+/// `RESUME_RECHERCHE.md`, avec pour chaque article son titre, sa description courte
+/// et un lien relatif vers ses fichiers (`article.html` s'il existe, sinon le
+/// Markdown), pour parcourir le dossier dans un navigateur, ouvert tel quel ou servi
+pub fn generate_index_html(
+    articles: &[WikipediaPage],
+    saved_paths: &[String],
+    folder: &str,
+    search_term: Option<&str>,
+) -> Result<(), ScraperError> {
+    let index_path = format!("{}/index.html", folder);
+    let heading = match search_term {
+        Some(term) => format!("Résumé de recherche : « {} »", term),
+        None => "Résumé de scraping".to_string(),
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", html_text(&heading)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_STYLE));
+    html.push_str(&format!("<h1>{}</h1>\n", html_text(&heading)));
+    html.push_str(&format!(
+        "<p class=\"meta\">{} — {} article(s)</p>\n",
+        chrono::Local::now().format("%d/%m/%Y à %H:%M:%S"),
+        articles.len()
+    ));
+
+    for (article, path) in articles.iter().zip(saved_paths) {
+        // Même emplacement que dans RESUME_RECHERCHE.md : fichiers à plat en mode
+        // mot-clé, un dossier par article sinon
+        let relative_path = relative_saved_path(path, folder);
+        let name = relative_path.strip_suffix(".md").unwrap_or(&relative_path);
+        let location = if search_term.is_some() {
+            PageLocation::Flat(name)
+        } else {
+            PageLocation::Folder(name)
+        };
+        let data_file = location.file("data.json", "json");
+        let target = [location.file("article.html", "html"), location.file("article.md", "md")]
+            .into_iter()
+            .find_map(|file| find_output_file(folder, &file))
+            .or_else(|| find_output_file(folder, &data_file))
+            .unwrap_or(data_file);
+        let href = target.split('/').map(|part| percent_encode(part, "%20")).collect::<Vec<_>>().join("/");
+
+        html.push_str(&format!(
+            "<h2><a href=\"./{}\">{}</a></h2>\n",
+            href,
+            html_text(&article.title)
+        ));
+        if article.summary.is_empty() {
+            html.push_str("<p><em>Résumé non disponible</em></p>\n");
+        } else {
+            html.push_str(&format!("<p>{}</p>\n", html_text(&short_summary(article))));
+        }
+        let source = match html_url(&article.url) {
+            Some(url) => format!("<a href=\"{}\">Wikipedia</a>", url),
+            None => html_text(&article.url),
+        };
+        html.push_str(&format!(
+            "<p class=\"meta\">Source : {} — {} section(s), {} mots</p>\n",
+            source,
+            article.sections.len(),
+            article.word_count
+        ));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    fs::write(&index_path, html)?;
+    info!("📄 Index HTML généré : {}", index_path);
+
+    Ok(())
+}
@@ -1,33 +1,61 @@
-use clap::Parser;
-use scraper::{Html, Selector, ElementRef};
-use serde::{Deserialize, Serialize};
+use clap::{CommandFactory, Parser};
+use sanitize_filename::sanitize;
 use std::error::Error;
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::Path;
-use std::net::TcpStream;
-use std::sync::Arc;
-use rustls::pki_types::ServerName;
-use sanitize_filename::sanitize;
-
-#[derive(Debug, Serialize, Deserialize)]
-struct WikipediaPage {
-    url: String,
-    title: String,
-    summary: String,
-    sections: Vec<String>,
-    links: Vec<String>,
-    images: Vec<String>,
-}
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use wikipedia_scraper::{
+    canonical_url, download_images, emit_event, find_section, generate_index_html,
+    generate_markdown, generate_search_summary, generate_section_markdown, info, load_ca_file,
+    open_database, page_from_html, page_schema, parse_url, parse_wikipedia_html, read_gzip_file,
+    remove_appendix_sections, resume_index, safe_file_name, save_page_data, save_page_to_db,
+    save_section, scrape_wikipedia, scrape_wikipedia_if_modified, search_wikipedia, set_json_events,
+    set_progress_bar, set_verbosity, validate_pages_json, verbosity, warn, write_all_json,
+    write_csv, write_manifest, ConnectionPool, HttpConfig, ImageSize, MarkdownOptions, OutputFormat,
+    PageCache, PageLocation, Proxy, RateLimiter, RobotsCache, SavedArticle, ScrapeOptions,
+    ScraperError, WikipediaPage, DEFAULT_APPENDIX_SECTIONS, DEFAULT_USER_AGENT,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use rusqlite::Connection;
 
 #[derive(Parser, Debug)]
 #[command(name = "Wikipedia Scraper")]
 #[command(about = "Scrape des pages Wikipedia en français", long_about = None)]
 struct Args {
-    /// Fichier contenant la liste des URLs Wikipedia (une par ligne)
+    /// Fichier contenant la liste des URLs Wikipedia (une par ligne, `-` pour l'entrée standard)
     #[arg(short, long)]
     fichier: Option<String>,
 
+    /// Lire les URLs sur l'entrée standard (une par ligne)
+    #[arg(long, conflicts_with = "fichier")]
+    stdin: bool,
+
+    /// Afficher le schéma JSON des données d'un article (`data.json`) et quitter
+    #[arg(long, exclusive = true)]
+    emit_schema: bool,
+
+    /// Vérifier que des fichiers JSON produits (data.json, all.json, articles.ndjson,
+    /// compressés ou non) respectent le schéma affiché par --emit-schema, puis quitter
+    #[arg(long, value_name = "FICHIER", num_args = 1.., exclusive = true)]
+    validate: Option<Vec<String>>,
+
+    /// Analyser une page Wikipedia enregistrée (fichier HTML) au lieu de la télécharger
+    #[arg(
+        long,
+        value_name = "FICHIER",
+        requires = "source_url",
+        conflicts_with_all = ["fichier", "stdin", "urls", "mot_cle", "depth", "download_images", "api_fallback"]
+    )]
+    from_html: Option<String>,
+
+    /// URL d'origine de la page donnée à --from-html (reprise dans le champ `url`)
+    #[arg(long, value_name = "URL", requires = "from_html")]
+    source_url: Option<String>,
+
     /// URLs Wikipedia séparées par des virgules
     #[arg(short, long)]
     urls: Option<String>,
@@ -36,6 +64,14 @@ struct Args {
     #[arg(short = 'k', long)]
     mot_cle: Option<String>,
 
+    /// Rechercher via l'API MediaWiki plutôt que la page HTML de recherche
+    #[arg(long)]
+    search_api: bool,
+
+    /// Ne garder que l'article dont le titre est exactement le mot-clé, sans recherche plein texte
+    #[arg(long)]
+    exact: bool,
+
     /// Nombre maximum de résultats à scraper (pour recherche par mot-clé)
     #[arg(short = 'n', long, default_value = "5")]
     nombre: usize,
@@ -43,802 +79,1551 @@ struct Args {
     /// Dossier de sortie pour les résultats
     #[arg(short, long, default_value = "resultats")]
     output: String,
+
+    /// Code langue du Wikipedia à interroger (fr, en, de...)
+    #[arg(short, long, default_value = "fr", value_parser = parse_lang)]
+    lang: String,
+
+    /// Télécharger les images de chaque article dans un sous-dossier images/
+    #[arg(long)]
+    download_images: bool,
+
+    /// Ne pas sauvegarder les pages d'homonymie
+    #[arg(long)]
+    skip_disambiguation: bool,
+
+    /// Suivre récursivement les liens internes jusqu'à cette profondeur (0 = désactivé)
+    #[arg(long, default_value = "0")]
+    depth: u32,
+
+    /// Arrêter après ce nombre d'articles sauvegardés (exploration ou gros fichiers d'URLs)
+    #[arg(long, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    max_pages: Option<usize>,
+
+    /// N'afficher que les erreurs
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Afficher les détails : requêtes, redirections, éléments trouvés par les sélecteurs
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Émettre un événement JSON par ligne sur stdout (pour les interfaces graphiques)
+    #[arg(long)]
+    json_events: bool,
+
+    /// Afficher les URLs qui seraient scrapées, sans rien télécharger ni écrire
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Nommer les dossiers de recherche et de lot sans horodatage (réutilisés d'une exécution à
+    /// l'autre ; --on-conflict overwrite pour écraser les articles précédents)
+    #[arg(long, conflicts_with = "timestamp_format")]
+    no_timestamp: bool,
+
+    /// Format strftime de l'horodatage ajouté aux noms de dossiers
+    #[arg(long, default_value = "%Y%m%d_%H%M%S", value_parser = parse_timestamp_format)]
+    timestamp_format: String,
+
+    /// Modèle du nom de fichier (ou de dossier) de chaque article : {index}, {title},
+    /// {date}, {lang} ; largeur complétée par des zéros avec {index:03}
+    #[arg(long, value_name = "MODÈLE", default_value = "{title}", value_parser = parse_filename_template)]
+    filename_template: String,
+
+    /// Fichier ou dossier d'article déjà existant : increment (suffixe _1, _2...), overwrite
+    /// ou skip ; avec --no-timestamp, overwrite pour qu'une relance réécrive le même dossier
+    #[arg(long, value_name = "POLITIQUE", default_value = "increment")]
+    on_conflict: OnConflict,
+
+    /// Reprendre un scraping interrompu dans ce dossier de recherche : les articles déjà
+    /// sauvegardés ne sont pas re-téléchargés, le résumé et le manifeste sont régénérés
+    #[arg(long, value_name = "DOSSIER", conflicts_with_all = ["stdout", "from_html"])]
+    resume: Option<String>,
+
+    /// Ne pas consulter ni alimenter le cache des pages déjà scrapées
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Dossier du cache des pages (par défaut : <output>/.cache)
+    #[arg(long, conflicts_with = "no_cache")]
+    cache_dir: Option<String>,
+
+    /// Écrire le Markdown de chaque article sur stdout (séparés par `---`) sans créer aucun fichier
+    #[arg(long, conflicts_with_all = ["json_events", "format", "download_images"])]
+    stdout: bool,
+
+    /// Retirer les sections d'annexe (« Notes et références », « Liens externes », « Bibliographie »...)
+    #[arg(long)]
+    skip_appendix_sections: bool,
+
+    /// Titres des sections d'annexe à retirer, séparés par des virgules (remplace la liste par défaut)
+    #[arg(long, requires = "skip_appendix_sections", value_delimiter = ',')]
+    appendix_sections: Option<Vec<String>>,
+
+    /// N'extraire que cette section (ex. « Histoire », sans tenir compte de la casse ni des
+    /// accents) dans section.md et section.txt ; les articles qui ne l'ont pas sont ignorés
+    #[arg(long, value_name = "TITRE")]
+    section: Option<String>,
+
+    /// Ne pas lister les liens internes (« Voir aussi ») dans le Markdown
+    #[arg(long)]
+    no_md_links: bool,
+
+    /// Ne pas inclure la galerie d'images dans le Markdown
+    #[arg(long)]
+    no_md_images: bool,
+
+    /// Formats de sortie, séparés par des virgules : json, markdown, txt, csv, html, yaml,
+    /// ou ndjson (seul, écrit au fil de l'eau)
+    #[arg(long, value_delimiter = ',', default_value = "json,markdown,txt")]
+    format: Vec<OutputFormat>,
+
+    /// Ajouter un BOM UTF-8 en tête des fichiers .txt et .csv (accents lisibles dans Excel sous Windows)
+    #[arg(long)]
+    bom: bool,
+
+    /// Compresser les fichiers de chaque article en gzip (`data.json.gz`, `article.md.gz`...)
+    #[arg(long)]
+    gzip_output: bool,
+
+    /// Nombre maximum de liens conservés par article (0 = pas de limite)
+    #[arg(long, default_value = "500")]
+    max_links: usize,
+
+    /// Espaces de noms dont les liens sont gardés, séparés par des virgules
+    /// (ex. `Catégorie,Portail`) ; par défaut, seuls les liens vers des articles le sont
+    #[arg(long, value_delimiter = ',')]
+    include_namespaces: Vec<String>,
+
+    /// Nombre maximum d'images conservées par article (0 = pas de limite)
+    #[arg(long, default_value = "20")]
+    max_images: usize,
+
+    /// Largeur et hauteur minimales (en pixels) d'une image conservée
+    #[arg(long, default_value = "100")]
+    min_image_size: u32,
+
+    /// Demander les miniatures d'images à cette largeur (en pixels)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "original_images")]
+    image_width: Option<u32>,
+
+    /// Garder l'URL des images originales en pleine résolution plutôt que des miniatures
+    #[arg(long)]
+    original_images: bool,
+
+    /// Si aucun résumé n'est trouvé dans le HTML, le demander à l'API REST de Wikipedia
+    #[arg(long)]
+    api_fallback: bool,
+
+    /// Extraire aussi le texte intégral de l'article (paragraphes, listes et titres,
+    /// sans tableaux ni infobox) dans `texte_complet.txt`
+    #[arg(long)]
+    full_text: bool,
+
+    /// Ne pas consulter robots.txt avant de scraper (à réserver aux usages avancés)
+    #[arg(long)]
+    ignore_robots: bool,
+
+    /// Nombre de pages scrapées en parallèle
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u64).range(1..))]
+    concurrency: u64,
+
+    /// Base SQLite dans laquelle enregistrer aussi les pages (mise à jour par URL)
+    #[arg(long)]
+    db: Option<String>,
+
+    /// Délai d'attente maximal des requêtes réseau (en secondes)
+    #[arg(long, default_value = "30", value_parser = clap::value_parser!(u64).range(1..))]
+    timeout: u64,
+
+    /// Nombre de nouvelles tentatives en cas d'erreur réseau transitoire
+    #[arg(long, default_value = "3")]
+    retries: u32,
+
+    /// Délai initial entre deux tentatives (en millisecondes, doublé à chaque essai)
+    #[arg(long, default_value = "500")]
+    retry_delay: u64,
+
+    /// User-Agent envoyé avec chaque requête
+    #[arg(long, default_value = DEFAULT_USER_AGENT)]
+    user_agent: String,
+
+    /// Proxy HTTP à utiliser (http://[utilisateur:mot_de_passe@]hôte:port)
+    #[arg(long, value_name = "URL", value_parser = parse_proxy)]
+    proxy: Option<Proxy>,
+
+    /// Fichier PEM de certificats d'autorités à accepter en plus des racines intégrées
+    /// (autorité d'entreprise, proxy qui inspecte le TLS...)
+    #[arg(long, value_name = "FICHIER", conflicts_with = "insecure")]
+    ca_file: Option<String>,
+
+    /// DANGEREUX : ne pas vérifier les certificats TLS (instance locale au certificat
+    /// auto-signé, tests uniquement)
+    #[arg(long)]
+    insecure: bool,
+
+    /// Nombre maximal de requêtes par seconde vers un même hôte
+    #[arg(long, default_value = "1.0", value_parser = parse_rate)]
+    rate: f64,
+
+    /// Délai minimal en millisecondes entre deux requêtes vers un même hôte (0 = aucun),
+    /// à la place de --rate
+    #[arg(long, conflicts_with = "rate")]
+    delay: Option<u64>,
+
+    /// Nombre maximal de redirections suivies pour une requête
+    #[arg(long, default_value = "5")]
+    max_redirects: u32,
+}
+
+/// Valide un code langue Wikipedia : 2 ou 3 lettres minuscules
+fn parse_lang(lang: &str) -> Result<String, String> {
+    let valid = (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase());
+    if valid {
+        Ok(lang.to_string())
+    } else {
+        Err(format!("code langue invalide \"{}\" (attendu : 2 ou 3 lettres minuscules, ex. fr, en, de)", lang))
+    }
+}
+
+/// Valide l'URL du proxy (`--proxy`)
+fn parse_proxy(url: &str) -> Result<Proxy, String> {
+    Proxy::parse(url).map_err(|e| e.to_string())
+}
+
+/// Valide un débit de requêtes : nombre strictement positif dont l'intervalle entre
+/// deux requêtes tient dans une `Duration`
+fn parse_rate(rate: &str) -> Result<f64, String> {
+    match rate.parse::<f64>() {
+        // Un débit infime donnerait un intervalle qu'une `Duration` ne peut pas représenter
+        Ok(value) if value.is_finite() && value > 0.0 => match Duration::try_from_secs_f64(1.0 / value) {
+            Ok(_) => Ok(value),
+            Err(_) => Err(format!("débit trop faible \"{}\" : intervalle entre deux requêtes trop long", rate)),
+        },
+        _ => Err(format!("débit invalide \"{}\" (attendu : nombre de requêtes par seconde > 0)", rate)),
+    }
+}
+
+/// Valide un format d'horodatage strftime (chrono refuse d'afficher un format invalide)
+fn parse_timestamp_format(format: &str) -> Result<String, String> {
+    let invalid = chrono::format::StrftimeItems::new(format)
+        .any(|item| matches!(item, chrono::format::Item::Error));
+    if invalid || format.is_empty() {
+        Err(format!("format d'horodatage invalide : \"{}\"", format))
+    } else {
+        Ok(format.to_string())
+    }
+}
+
+/// Valide un modèle de nom de fichier : champs connus, et au moins `{title}` ou
+/// `{index}` pour que deux articles n'aient pas le même nom
+fn parse_filename_template(template: &str) -> Result<String, String> {
+    if !template.contains("{title") && !template.contains("{index") {
+        return Err(format!("le modèle \"{}\" doit contenir {{title}} ou {{index}}", template));
+    }
+    expand_filename_template(template, 1, "Titre", "fr")?;
+    Ok(template.to_string())
+}
+
+/// Remplace les champs d'un modèle `--filename-template` : `{index}` (position de
+/// l'article, à partir de 1), `{title}`, `{date}` (AAAA-MM-JJ) et `{lang}` ; `{champ:03}`
+/// complète la valeur par des zéros à gauche jusqu'à 3 caractères
+fn expand_filename_template(template: &str, index: usize, title: &str, lang: &str) -> Result<String, String> {
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("accolade non fermée dans \"{}\"", template))?;
+        let (field, width) = match rest[start + 1..end].split_once(':') {
+            Some((field, width)) => {
+                let width = width.parse::<usize>().map_err(|_| format!("largeur invalide \"{}\" pour {{{}}}", width, field))?;
+                (field, width)
+            }
+            None => (&rest[start + 1..end], 0),
+        };
+        let value = match field {
+            "index" => index.to_string(),
+            "title" => title.to_string(),
+            "date" => chrono::Local::now().format("%Y-%m-%d").to_string(),
+            "lang" => lang.to_string(),
+            _ => return Err(format!("champ inconnu {{{}}} (disponibles : index, title, date, lang)", field)),
+        };
+        name.push_str(&format!("{:0>width$}", value, width = width));
+        rest = &rest[end + 1..];
+    }
+    name.push_str(rest);
+    Ok(name)
+}
+
+/// Conduite à tenir quand le fichier ou le dossier d'un article existe déjà (`--on-conflict`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OnConflict {
+    /// Nouveau nom suffixé : `Titre_1`, `Titre_2`...
+    Increment,
+    /// Réécrire les fichiers existants
+    Overwrite,
+    /// Ne pas sauvegarder l'article
+    Skip,
+}
+
+impl std::str::FromStr for OnConflict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "increment" => Ok(OnConflict::Increment),
+            "overwrite" => Ok(OnConflict::Overwrite),
+            "skip" => Ok(OnConflict::Skip),
+            _ => Err(format!("politique inconnue \"{}\" (attendu : increment, overwrite ou skip)", s)),
+        }
+    }
+}
+
+/// Paramètres de sauvegarde communs à tous les articles d'une exécution
+struct OutputContext<'a> {
+    search_folder: &'a str,
+    /// Recherche par mot-clé : un fichier markdown par article à la racine du dossier
+    keyword_mode: bool,
+    download_images: bool,
+    /// Nombre maximal d'articles sauvegardés (`--max-pages`)
+    max_pages: Option<usize>,
+    /// Markdown écrit sur stdout au lieu des fichiers (`--stdout`)
+    stdout: bool,
+    /// Fichiers écrits pour chaque article (`--format`)
+    formats: &'a [OutputFormat],
+    /// Liens et images repris dans le Markdown
+    markdown: MarkdownOptions,
+    /// BOM UTF-8 en tête des fichiers texte (`--bom`)
+    bom: bool,
+    /// Fichiers des articles compressés en `.gz` (`--gzip-output`)
+    gzip: bool,
+    /// Section seule écrite dans `section.md` et `section.txt` (`--section`)
+    section: Option<&'a str>,
+    /// Reprise d'un scraping (`--resume`) : les articles déjà sauvegardés sont conservés
+    resume: bool,
+    /// Modèle des noms de fichiers des articles (`--filename-template`)
+    filename_template: &'a str,
+    /// Fichiers d'un article déjà présents (`--on-conflict`)
+    on_conflict: OnConflict,
+    lang: &'a str,
+    http_config: &'a HttpConfig,
+}
+
+impl OutputContext<'_> {
+    /// Nom de fichier (ou de dossier) d'un article, d'après `--filename-template`
+    fn file_name(&self, index: usize, title: &str) -> String {
+        let name = expand_filename_template(self.filename_template, index + 1, title, self.lang)
+            .expect("modèle validé par parse_filename_template");
+        safe_file_name(&name, index + 1)
+    }
+
+    /// Indique si un article a déjà des fichiers à ce chemin (sans extension en
+    /// recherche par mot-clé, dossier de l'article sinon)
+    fn saved_at(&self, path: &str) -> bool {
+        if self.keyword_mode {
+            PageLocation::Flat(path).exists(self.formats)
+        } else {
+            Path::new(path).exists()
+        }
+    }
+
+    /// Chemin où sauvegarder un article de nom `base_name`, selon `--on-conflict` ;
+    /// `None` si l'article doit être ignoré. La politique s'applique aussi en
+    /// reprise : les articles déjà sauvegardés y sont écartés avant le scraping.
+    fn output_path(&self, base_name: &str) -> Option<String> {
+        let path = format!("{}/{}", self.search_folder, base_name);
+        if !self.saved_at(&path) {
+            return Some(path);
+        }
+        match self.on_conflict {
+            OnConflict::Overwrite => Some(path),
+            OnConflict::Skip => None,
+            OnConflict::Increment => (1..)
+                .map(|i| format!("{}_{}", path, i))
+                .find(|candidate| !self.saved_at(candidate)),
+        }
+    }
+}
+
+/// Page à scraper : position dans l'ordre de découverte, URL et profondeur
+struct CrawlJob {
+    index: usize,
+    url: String,
+    depth: u32,
+}
+
+/// File d'attente partagée par les workers ; en mode `--depth`, les liens des
+/// pages scrapées y sont ajoutés au fur et à mesure
+struct CrawlQueue {
+    state: Mutex<CrawlQueueState>,
+    ready: Condvar,
+}
+
+struct CrawlQueueState {
+    pending: VecDeque<CrawlJob>,
+    /// URLs canoniques déjà mises en file, pour éviter les cycles
+    visited: HashSet<String>,
+    /// Nombre total d'URLs mises en file
+    enqueued: usize,
+    /// Pages en cours de traitement, qui peuvent encore ajouter des liens
+    in_progress: usize,
+}
+
+impl CrawlQueueState {
+    fn push(&mut self, url: String, depth: u32) {
+        if self.visited.insert(canonical_url(&url)) {
+            self.pending.push_back(CrawlJob { index: self.enqueued, url, depth });
+            self.enqueued += 1;
+        }
+    }
+}
+
+impl CrawlQueue {
+    fn new(urls: &[String]) -> Self {
+        let mut state = CrawlQueueState {
+            pending: VecDeque::new(),
+            visited: HashSet::new(),
+            enqueued: 0,
+            in_progress: 0,
+        };
+        for url in urls {
+            state.push(url.clone(), 0);
+        }
+        CrawlQueue { state: Mutex::new(state), ready: Condvar::new() }
+    }
+
+    /// Prochaine page à scraper ; attend tant que d'autres workers peuvent encore
+    /// ajouter des liens, et retourne `None` quand tout est terminé
+    fn next(&self) -> Option<CrawlJob> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if let Some(job) = state.pending.pop_front() {
+                state.in_progress += 1;
+                return Some(job);
+            }
+            if state.in_progress == 0 {
+                return None;
+            }
+            state = self.ready.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Marque une page comme traitée et met en file les liens trouvés
+    fn complete(&self, links: Vec<String>, depth: u32) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        for link in links {
+            state.push(link, depth);
+        }
+        state.in_progress -= 1;
+        self.ready.notify_all();
+    }
+
+    fn total(&self) -> usize {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).enqueued
+    }
+}
+
+/// État partagé entre les workers pendant le scraping
+struct RunState {
+    /// Articles retenus, avec leur position dans la liste d'URLs d'origine, le
+    /// chemin où ils ont été sauvegardés et l'URL demandée (avant redirections)
+    articles: Vec<(usize, WikipediaPage, String, String)>,
+    /// Articles déjà sauvegardés : clé de déduplication → URL de l'article
+    seen_articles: HashMap<String, String>,
+    database: Option<Connection>,
+    /// Flux `articles.ndjson` en mode `--format ndjson`
+    ndjson: Option<io::BufWriter<fs::File>>,
+    /// Pages non scrapées ou non sauvegardées car `--max-pages` était atteint
+    skipped_pages: usize,
+    /// Pages laissées de côté après un Ctrl-C
+    interrupted_pages: usize,
+}
+
+/// Positionné par le gestionnaire de Ctrl-C : les workers terminent la page en cours
+/// puis vident la file sans scraper le reste
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+impl RunState {
+    /// Vrai si `--max-pages` articles ont déjà été sauvegardés (doublons exclus)
+    fn page_limit_reached(&self, max_pages: Option<usize>) -> bool {
+        max_pages.is_some_and(|max| self.seen_articles.len() >= max)
+    }
 }
 
 /// Fonction principale
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
+    if args.json_events {
+        // Les événements remplacent les messages lisibles sur stdout
+        set_json_events(true);
+        set_verbosity(0);
+    } else if args.stdout || args.quiet {
+        // Avec --stdout, la sortie standard est réservée au Markdown des articles
+        set_verbosity(0);
+    } else if args.verbose {
+        set_verbosity(2);
+    }
+
+    if args.emit_schema {
+        println!("{}", serde_json::to_string_pretty(&page_schema())?);
+        return Ok(());
+    }
+    if let Some(files) = &args.validate {
+        return validate_files(files);
+    }
+
+    // Un flux NDJSON est réécrit à chaque exécution : il ne peut pas être complété
+    if args.resume.is_some() && args.format.contains(&OutputFormat::Ndjson) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--resume ne peut pas être utilisé avec --format ndjson",
+            )
+            .exit();
+    }
+    if let Some(folder) = args.resume.as_deref().filter(|folder| !Path::new(folder).is_dir()) {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::ValueValidation,
+                format!("--resume : le dossier {} n'existe pas", folder),
+            )
+            .exit();
+    }
+
+    // NDJSON remplace les fichiers par article : il ne se combine pas avec les autres formats
+    if args.format.contains(&OutputFormat::Ndjson) && args.format.len() > 1 {
+        Args::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--format ndjson ne peut pas être combiné avec d'autres formats",
+            )
+            .exit();
+    }
+
+    // Autorités supplémentaires (--ca-file), lues une fois pour toutes les connexions
+    let extra_root_certs = match &args.ca_file {
+        Some(path) => load_ca_file(path).unwrap_or_else(|e| {
+            Args::command()
+                .error(
+                    clap::error::ErrorKind::Io,
+                    format!("impossible de charger les certificats de {} : {}", path, e),
+                )
+                .exit()
+        }),
+        None => Vec::new(),
+    };
+    let http_config = HttpConfig {
+        timeout: Duration::from_secs(args.timeout),
+        retries: args.retries,
+        retry_delay: Duration::from_millis(args.retry_delay),
+        max_redirects: args.max_redirects,
+        pool: Arc::new(ConnectionPool::default()),
+        rate_limiter: Arc::new(match args.delay {
+            Some(delay) => RateLimiter::with_interval(Duration::from_millis(delay)),
+            None => RateLimiter::new(args.rate),
+        }),
+        user_agent: args.user_agent.clone(),
+        proxy: args.proxy.clone(),
+        extra_root_certs,
+        insecure: args.insecure,
+    };
+    if args.insecure {
+        // Affiché même avec -q : la connexion n'authentifie plus le serveur
+        eprintln!("⚠⚠ --insecure : les certificats TLS ne sont PAS vérifiés. À réserver aux tests sur une instance locale ⚠⚠");
+    }
+
+    // Page enregistrée (--from-html) : lue une fois ici, analysée sans aucun accès réseau
+    let from_html = args.from_html.as_ref().map(|path| {
+        fs::read_to_string(path).unwrap_or_else(|e| {
+            Args::command()
+                .error(
+                    clap::error::ErrorKind::Io,
+                    format!("impossible de lire le fichier HTML {} : {}", path, e),
+                )
+                .exit()
+        })
+    });
 
     // Récupérer la liste des URLs (et mot-clé utilisé en mode interactif le cas échéant)
-    let (urls, interactive_keyword) = if let Some(mot_cle) = args.mot_cle.clone() {
+    let (urls, interactive_keyword) = if let Some(source_url) = &args.source_url {
+        // Une seule « URL » : celle d'origine de la page enregistrée
+        if let Err(e) = parse_url(source_url) {
+            Args::command()
+                .error(
+                    clap::error::ErrorKind::ValueValidation,
+                    format!("--source-url invalide ({}) : {}", source_url, e),
+                )
+                .exit();
+        }
+        info!("\n📄 Analyse de la page enregistrée {}", args.from_html.as_deref().unwrap_or_default());
+        (vec![source_url.clone()], None)
+    } else if let Some(mot_cle) = args.mot_cle.clone() {
         // Recherche par mot-clé
-        println!("\n🔍 Recherche Wikipedia pour: \"{}\"", mot_cle);
-        let resultats = rechercher_wikipedia(&mot_cle, args.nombre)?;
+        info!("\n🔍 Recherche Wikipedia pour: \"{}\"", mot_cle);
+        let resultats = search_wikipedia(
+            &mot_cle,
+            args.nombre,
+            &args.lang,
+            args.search_api,
+            args.exact,
+            &args.include_namespaces,
+            &http_config,
+        )?;
         
         if resultats.is_empty() {
             eprintln!("Aucun résultat trouvé pour \"{}\"", mot_cle);
             return Ok(());
         }
         
-        println!("\n✓ {} résultat(s) trouvé(s):\n", resultats.len());
+        info!("\n✓ {} résultat(s) trouvé(s):\n", resultats.len());
         for (i, url) in resultats.iter().enumerate() {
-            println!("  {}. {}", i + 1, url);
+            info!("  {}. {}", i + 1, url);
         }
-        println!();
+        info!();
         
         (resultats, Some(mot_cle))
-    } else if let Some(fichier) = args.fichier {
+    } else if args.stdin || args.fichier.as_deref() == Some("-") {
+        // Lecture des URLs depuis l'entrée standard (pipe)
+        let contenu = io::read_to_string(io::stdin())?;
+        let urls = parse_url_lines(&contenu);
+        info!("\n📥 Lecture de {} URL(s) depuis l'entrée standard", urls.len());
+        (urls, None)
+    } else if let Some(fichier) = &args.fichier {
         // Lecture des URLs depuis un fichier
         let contenu = fs::read_to_string(fichier)?;
-        let urls: Vec<String> = contenu.lines().map(|line| line.to_string()).collect();
-        println!("\n📂 Chargement de {} URL(s) depuis le fichier", urls.len());
+        let urls = parse_url_lines(&contenu);
+        info!("\n📂 Chargement de {} URL(s) valide(s) depuis le fichier", urls.len());
         (urls, None)
-    } else if let Some(urls_str) = args.urls {
+    } else if let Some(urls_str) = &args.urls {
         // URLs fournies en ligne de commande
         (urls_str.split(',').map(|s| s.trim().to_string()).collect(), None)
     } else {
         // Mode interactif
-        get_urls_interactif(args.nombre)?
+        get_urls_interactif(
+            args.nombre,
+            &args.lang,
+            args.search_api,
+            args.exact,
+            &args.include_namespaces,
+            &http_config,
+        )?
     };
+    let interactive = args.mot_cle.is_none()
+        && args.fichier.is_none()
+        && args.urls.is_none()
+        && !args.stdin
+        && args.from_html.is_none();
+
     // Déterminer le mot-clé effectif (option --mot_cle ou mot-clé saisi en mode interactif)
     let mot_cle_effectif: Option<String> = args.mot_cle.clone().or(interactive_keyword);
 
-    let urls = urls;
-
     if urls.is_empty() {
         eprintln!("Erreur: Aucune URL fournie");
         return Ok(());
     }
 
-    // Créer le dossier de sortie principal
-    fs::create_dir_all(&args.output)?;
+    // Aperçu : afficher les URLs qui seraient scrapées (après déduplication) et s'arrêter
+    if args.dry_run {
+        let mut seen = HashSet::new();
+        let unique_urls: Vec<&String> = urls.iter().filter(|url| seen.insert(canonical_url(url))).collect();
+        println!("\n🔎 Mode --dry-run : {} URL(s) seraient scrapées\n", unique_urls.len());
+        for (i, url) in unique_urls.iter().enumerate() {
+            println!("  {}. {}", i + 1, url);
+        }
+        return Ok(());
+    }
+
+    // Créer le dossier de sortie principal (rien n'est écrit sur disque avec --stdout)
+    if !args.stdout {
+        fs::create_dir_all(&args.output)?;
+    }
 
     // Créer un dossier spécifique pour cette recherche
-    let search_folder = if let Some(mot_cle) = &mot_cle_effectif {
+    // Suffixe horodaté des dossiers ; avec --no-timestamp le nom reste stable d'une exécution à l'autre
+    let folder_name = |base: &str| {
+        if args.no_timestamp {
+            sanitize(base)
+        } else {
+            let timestamp = chrono::Local::now().format(&args.timestamp_format);
+            sanitize(format!("{}_{}", base, timestamp))
+        }
+    };
+    let search_folder = if let Some(folder) = &args.resume {
+        // Reprise : on complète le dossier d'une exécution précédente
+        folder.trim_end_matches('/').to_string()
+    } else if let Some(mot_cle) = &mot_cle_effectif {
         // Recherche par mot-clé : créer un dossier avec le mot-clé et timestamp
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let folder_name = format!("{}_{}", sanitize(mot_cle), timestamp);
-        format!("{}/{}", args.output, folder_name)
+        format!("{}/{}", args.output, folder_name(mot_cle))
     } else if urls.len() > 1 {
         // Plusieurs URLs : créer un dossier avec timestamp
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        format!("{}/batch_{}", args.output, timestamp)
+        format!("{}/{}", args.output, folder_name("batch"))
     } else {
         // Une seule URL : pas de dossier parent supplémentaire
         args.output.clone()
     };
 
-    fs::create_dir_all(&search_folder)?;
+    if !args.stdout {
+        fs::create_dir_all(&search_folder)?;
+    }
 
-    println!("\n=== Scraping de {} page(s) ===\n", urls.len());
-    println!("📁 Dossier de recherche : {}\n", search_folder);
+    info!("\n=== Scraping de {} page(s) ===\n", urls.len());
+    info!("📁 Dossier de recherche : {}\n", search_folder);
 
-    // Scraper chaque URL
-    let mut scraped_articles = Vec::new();
-    
-    for (index, url) in urls.iter().enumerate() {
-        println!("[{}/{}] Scraping de: {}", index + 1, urls.len(), url);
+    // Ouvrir la base SQLite si demandée
+    let database = match &args.db {
+        Some(path) => Some(open_database(path)?),
+        None => None,
+    };
 
-    match scrape_wikipedia(url, mot_cle_effectif.as_deref()) {
-            Ok(page_data) => {
-                // Déduplication par titre : si on a déjà traité un article avec le même titre (cas insensible), on l'ignore
-                let title_lower = page_data.title.to_lowercase();
-                if scraped_articles.iter().any(|a: &WikipediaPage| a.title.to_lowercase() == title_lower) {
-                    println!("  ⚠ Article déjà traité (même titre) : {} — ignoré\n", page_data.title);
-                    continue;
-                }
+    // Cache des pages : une page inchangée depuis le dernier passage (304) n'est pas re-scrapée
+    // (avec --stdout, seulement si un dossier de cache est donné explicitement)
+    let cache = if args.no_cache || from_html.is_some() || (args.stdout && args.cache_dir.is_none()) {
+        None
+    } else {
+        let cache_dir = args.cache_dir.clone().unwrap_or_else(|| format!("{}/.cache", args.output));
+        Some(PageCache::new(&cache_dir)?)
+    };
 
-                // Si la recherche est par mot-clé (CLI ou interactif), on écrit uniquement le fichier markdown
-                if mot_cle_effectif.is_some() {
-                    // Nom de fichier unique
-                    let base_name = sanitize(&page_data.title);
-                    let mut file_name = format!("{}.md", base_name);
-                    let mut i = 1;
-                    let mut full_path = format!("{}/{}", search_folder, file_name);
-                    while Path::new(&full_path).exists() {
-                        file_name = format!("{}_{}.md", base_name, i);
-                        full_path = format!("{}/{}", search_folder, file_name);
-                        i += 1;
-                    }
+    let output = OutputContext {
+        search_folder: &search_folder,
+        keyword_mode: mot_cle_effectif.is_some(),
+        download_images: args.download_images,
+        max_pages: args.max_pages,
+        stdout: args.stdout,
+        formats: &args.format,
+        markdown: MarkdownOptions {
+            include_links: !args.no_md_links,
+            include_images: !args.no_md_images,
+        },
+        bom: args.bom,
+        gzip: args.gzip_output,
+        section: args.section.as_deref(),
+        resume: args.resume.is_some(),
+        filename_template: &args.filename_template,
+        on_conflict: args.on_conflict,
+        lang: &args.lang,
+        http_config: &http_config,
+    };
+    // Reprise : articles déjà sauvegardés, retrouvés par leur URL
+    let already_saved = if output.resume {
+        resume_index(output.search_folder, output.keyword_mode)
+    } else {
+        HashMap::new()
+    };
+    // En NDJSON, les articles sont écrits au fil de l'eau au lieu d'être gardés en mémoire
+    let ndjson = if args.format.contains(&OutputFormat::Ndjson) && !args.stdout {
+        let ndjson_path = format!("{}/articles.ndjson", search_folder);
+        Some(io::BufWriter::new(fs::File::create(ndjson_path)?))
+    } else {
+        None
+    };
 
-                    let markdown_content = generate_markdown(&page_data);
-                    fs::write(&full_path, markdown_content)?;
-
-                    println!("  ✓ Titre: {}", page_data.title);
-                    println!("  ✓ Sections: {}", page_data.sections.len());
-                    println!("  ✓ Liens: {}", page_data.links.len());
-                    println!("  ✓ Images: {}", page_data.images.len());
-                    println!("  ✓ Sauvegardé dans: {}\n", full_path);
-
-                    // Ajouter à la liste pour le résumé global
-                    scraped_articles.push(page_data);
-                } else {
-                    // Comportement précédent : créer un dossier par page et y sauvegarder tous les fichiers
-                    let page_folder = format!(
-                        "{}/{}",
-                        search_folder,
-                        sanitize(&page_data.title)
-                    );
-                    fs::create_dir_all(&page_folder)?;
-
-                    // Sauvegarder les données
-                    save_page_data(&page_data, &page_folder)?;
-
-                    println!("  ✓ Titre: {}", page_data.title);
-                    println!("  ✓ Sections: {}", page_data.sections.len());
-                    println!("  ✓ Liens: {}", page_data.links.len());
-                    println!("  ✓ Images: {}", page_data.images.len());
-                    println!("  ✓ Sauvegardé dans: {}\n", page_folder);
-                    
-                    // Ajouter à la liste pour le résumé global
-                    scraped_articles.push(page_data);
-                }
-            }
-            Err(e) => {
-                eprintln!("  ✗ Erreur: {}\n", e);
-            }
+    // Barre de progression pour un usage interactif : seulement sur un terminal et au niveau
+    // de verbosité normal ; elle remplace alors les messages détaillés de chaque page, les
+    // avertissements restant affichés au-dessus d'elle
+    let progress = (verbosity() == 1 && io::stdout().is_terminal()).then(|| {
+        let bar = ProgressBar::new(urls.len() as u64);
+        bar.set_style(
+            ProgressStyle::with_template("{spinner} [{bar:40.cyan/blue}] {pos}/{len} {wide_msg}")
+                .expect("modèle de barre de progression valide")
+                .progress_chars("=> "),
+        );
+        bar.enable_steady_tick(Duration::from_millis(120));
+        set_progress_bar(Some(bar.clone()));
+        bar
+    });
+    let error_count = AtomicUsize::new(0);
+    // Affiche un message sans abîmer la barre de progression
+    let report = |message: &str| match &progress {
+        Some(bar) => bar.suspend(|| eprintln!("{}", message)),
+        None => eprintln!("{}", message),
+    };
+
+    let state = Mutex::new(RunState {
+        articles: Vec::new(),
+        seen_articles: HashMap::new(),
+        database,
+        ndjson,
+        skipped_pages: 0,
+        interrupted_pages: 0,
+    });
+    let scrape_options = ScrapeOptions {
+        max_links: args.max_links,
+        max_images: args.max_images,
+        min_image_size: args.min_image_size,
+        image_size: match args.image_width {
+            Some(width) => ImageSize::Width(width),
+            None if args.original_images => ImageSize::Original,
+            None => ImageSize::AsPage,
+        },
+        api_fallback: args.api_fallback,
+        include_namespaces: args.include_namespaces.clone(),
+        full_text: args.full_text,
+    };
+    // Titres des sections d'annexe à retirer (--skip-appendix-sections)
+    let appendix_sections: Option<Vec<String>> = args.skip_appendix_sections.then(|| {
+        args.appendix_sections.clone().unwrap_or_else(|| {
+            DEFAULT_APPENDIX_SECTIONS.iter().map(|title| title.to_string()).collect()
+        })
+    });
+    let queue = CrawlQueue::new(&urls);
+    // Un seul choix d'homonyme demandé à la fois, même avec plusieurs workers
+    let prompt_lock = Mutex::new(());
+    let robots = RobotsCache::default();
+    // Termine une URL de la file et fait avancer la barre de progression
+    let complete_job = |links: Vec<String>, depth: u32| {
+        queue.complete(links, depth);
+        if let Some(bar) = &progress {
+            bar.set_length(queue.total() as u64);
+            bar.inc(1);
         }
+    };
 
-        // Pause entre les requêtes pour être respectueux
-        std::thread::sleep(std::time::Duration::from_secs(1));
-    }
+    // Un premier Ctrl-C arrête proprement après la page en cours (résumé et manifeste
+    // compris) ; un second interrompt immédiatement
+    ctrlc::set_handler(|| {
+        if STOP_REQUESTED.swap(true, Ordering::SeqCst) {
+            std::process::exit(130);
+        }
+        eprintln!("\n⏹ Interruption demandée : fin de la page en cours (Ctrl-C à nouveau pour quitter)");
+    })?;
+
+    // Scraper les URLs avec `concurrency` workers qui se partagent la file d'attente
+    std::thread::scope(|scope| {
+        for _ in 0..args.concurrency {
+            scope.spawn(|| {
+                while let Some(job) = queue.next() {
+                    // Ctrl-C : on vide la file sans scraper
+                    if STOP_REQUESTED.load(Ordering::SeqCst) {
+                        state.lock().unwrap_or_else(|e| e.into_inner()).interrupted_pages += 1;
+                        complete_job(Vec::new(), job.depth + 1);
+                        continue;
+                    }
 
-    // Générer un fichier récapitulatif de la recherche
-    if scraped_articles.len() > 1 {
-        generate_search_summary(&scraped_articles, &search_folder, args.mot_cle.as_deref())?;
-    }
+                    // Limite --max-pages atteinte : on vide la file sans scraper
+                    {
+                        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                        if state.page_limit_reached(output.max_pages) {
+                            state.skipped_pages += 1;
+                            drop(state);
+                            complete_job(Vec::new(), job.depth + 1);
+                            continue;
+                        }
+                    }
 
-    println!("=== Scraping terminé ===");
-    println!("📂 Résultats disponibles dans: {}", search_folder);
-    println!("📊 {} article(s) traité(s) avec succès", scraped_articles.len());
+                    if args.depth > 0 {
+                        info!(
+                            "[{}/{}] Scraping de: {} (profondeur {})",
+                            job.index + 1,
+                            queue.total(),
+                            job.url,
+                            job.depth
+                        );
+                    } else {
+                        info!("[{}/{}] Scraping de: {}", job.index + 1, queue.total(), job.url);
+                    }
+                    emit_event(
+                        "page_started",
+                        serde_json::json!({ "index": job.index, "url": job.url, "depth": job.depth }),
+                    );
+                    if let Some(bar) = &progress {
+                        bar.set_message(job.url.clone());
+                    }
 
-    Ok(())
-}
+                    // Liens à explorer au niveau suivant (mode --depth uniquement)
+                    let mut next_links = Vec::new();
+
+                    let allowed = args.ignore_robots
+                        || from_html.is_some()
+                        || parse_url(&job.url)
+                            .map(|url_parts| robots.is_allowed(&url_parts, &http_config))
+                            .unwrap_or(true);
+                    if !allowed {
+                        info!("  ⛔ URL interdite par robots.txt — ignorée\n");
+                        emit_event(
+                            "page_skipped",
+                            serde_json::json!({ "index": job.index, "url": job.url, "reason": "robots" }),
+                        );
+                        complete_job(next_links, job.depth + 1);
+                        continue;
+                    }
 
-/// Fonction pour rechercher des articles sur Wikipedia par mot-clé
-fn rechercher_wikipedia(mot_cle: &str, max_resultats: usize) -> Result<Vec<String>, Box<dyn Error>> {
-    let mot_cle_encode = url_encode(mot_cle);
-    // version mot-clé adaptée pour l'URL (espaces -> _)
-    let kw_url = mot_cle.to_lowercase().replace(' ', "_");
-
-    // URL directe (fallback)
-    let direct_url = format!("https://fr.wikipedia.org/wiki/{}", mot_cle_encode);
-
-    // Récupérer la page de recherche HTML
-    println!("  Récupération de la page de recherche https://fr.wikipedia.org/w/index.php?search={}", mot_cle);
-    // Forcer l'affichage de la page Special:Search pour obtenir la liste de résultats
-    let search_path_html = format!("/w/index.php?search={}&title=Special%3ASearch&fulltext=1", mot_cle_encode);
-
-    let mut results: Vec<String> = Vec::new();
-
-    if let Ok(html_content) = https_get("fr.wikipedia.org", &search_path_html) {
-        let document = Html::parse_document(&html_content);
-
-        // Extraire uniquement les liens listés dans la page de recherche
-        // Priorité aux éléments standard de la recherche :
-        // - `div.mw-search-result-heading a` (nouveau markup)
-        // - `div.mw-search-results li a` (fallback historique)
-        let selectors = [
-            "div.mw-search-result-heading a",
-            "div.mw-search-results li a",
-            "ul.mw-search-results li a",
-        ];
-
-        for sel in selectors.iter() {
-            if results.len() >= max_resultats { break; }
-            if let Ok(s) = Selector::parse(sel) {
-                for el in document.select(&s) {
-                    if results.len() >= max_resultats { break; }
-                    if let Some(href) = el.value().attr("href") {
-                        if href.starts_with("/wiki/") && !href.contains(':') && !href.contains('#') {
-                            let url = format!("https://fr.wikipedia.org{}", href);
-                            if !results.contains(&url) {
-                                results.push(url);
+                    // Reprise : un article déjà sauvegardé n'est pas re-téléchargé ; sans ses
+                    // données (Markdown seul d'une exécution interrompue), il est re-scrapé
+                    // et réécrit à son emplacement d'origine
+                    let saved = already_saved.get(&canonical_url(&job.url));
+                    if let Some(SavedArticle { base, page: Some(page_data) }) = saved {
+                        let saved_path = saved_path(base, &output);
+                        info!("  ⏭ Déjà sauvegardé dans {} — ignoré (--resume)\n", saved_path);
+                        emit_event(
+                            "page_skipped",
+                            serde_json::json!({ "index": job.index, "url": job.url, "reason": "resume" }),
+                        );
+                        if job.depth < args.depth {
+                            next_links = page_data.links.clone();
+                        }
+                        keep_saved_article(job.index, &job.url, page_data.clone(), saved_path, &state);
+                        complete_job(next_links, job.depth + 1);
+                        continue;
+                    }
+                    let resume_base = saved.map(|saved| saved.base.as_str());
+
+                    let scrape = |url: &str| -> Result<WikipediaPage, Box<dyn Error>> {
+                        if let Some(html) = &from_html {
+                            let page_data = parse_wikipedia_html(
+                                html,
+                                url,
+                                mot_cle_effectif.as_deref(),
+                                &args.lang,
+                                &scrape_options,
+                            );
+                            if page_data.is_empty() {
+                                return Err(ScraperError::EmptyPage(page_data.url).into());
+                            }
+                            return Ok(page_data);
+                        }
+                        let Some(cache) = &cache else {
+                            return Ok(scrape_wikipedia(
+                                url,
+                                mot_cle_effectif.as_deref(),
+                                &args.lang,
+                                &scrape_options,
+                                &http_config,
+                            )?);
+                        };
+                        let cached = cache.get(url);
+                        match scrape_wikipedia_if_modified(
+                            url,
+                            cached.as_ref().map(|entry| &entry.validators),
+                            mot_cle_effectif.as_deref(),
+                            &args.lang,
+                            &scrape_options,
+                            &http_config,
+                        )? {
+                            Some((page_data, fetched)) => {
+                                cache.put(url, &fetched)?;
+                                Ok(page_data)
+                            }
+                            None => {
+                                // HTML en cache ré-analysé avec les options de cette exécution
+                                let entry = cached.ok_or("Réponse 304 sans copie en cache")?;
+                                info!("  ♻ Page inchangée depuis le {} — HTML du cache", entry.fetched_at);
+                                Ok(page_from_html(
+                                    &entry.html,
+                                    &entry.final_url,
+                                    mot_cle_effectif.as_deref(),
+                                    &args.lang,
+                                    &scrape_options,
+                                    &http_config,
+                                )?)
                             }
                         }
+                    };
+                    let mut store = |mut page_data: WikipediaPage| {
+                        page_data.crawl_depth = job.depth;
+                        if let Some(titles) = &appendix_sections {
+                            remove_appendix_sections(&mut page_data, titles);
+                        }
+                        if let Some(name) = &args.section {
+                            if find_section(&page_data, name).is_none() {
+                                info!("  ⏭ Section « {} » introuvable dans {} — article ignoré\n", name, page_data.title);
+                                emit_event(
+                                    "page_skipped",
+                                    serde_json::json!({
+                                        "index": job.index,
+                                        "url": page_data.url,
+                                        "reason": "section_not_found",
+                                    }),
+                                );
+                                return Ok(());
+                            }
+                        }
+                        if job.depth < args.depth {
+                            next_links = page_data.links.clone();
+                        }
+                        if let Some(bar) = &progress {
+                            bar.set_message(page_data.title.clone());
+                        }
+                        store_article(job.index, &job.url, resume_base, page_data, &state, &output)
+                    };
+
+                    let result = scrape(&job.url).and_then(|page_data| {
+                        if page_data.disambiguation {
+                            if args.skip_disambiguation {
+                                info!("  ⏭ Page d'homonymie ignorée : {}\n", page_data.title);
+                                emit_event(
+                                    "page_skipped",
+                                    serde_json::json!({
+                                        "index": job.index,
+                                        "url": page_data.url,
+                                        "reason": "disambiguation",
+                                    }),
+                                );
+                                return Ok(());
+                            }
+
+                            // En mode interactif, proposer de scraper l'un des articles listés
+                            if interactive && !page_data.disambiguation_options.is_empty() {
+                                let _prompt = prompt_lock.lock().unwrap_or_else(|e| e.into_inner());
+                                let choix = match &progress {
+                                    Some(bar) => bar.suspend(|| choisir_homonyme(&page_data))?,
+                                    None => choisir_homonyme(&page_data)?,
+                                };
+                                if let Some(target) = choix {
+                                    info!("  → Scraping de: {}", target);
+                                    return store(scrape(&target)?);
+                                }
+                            }
+                        }
+                        store(page_data)
+                    });
+                    if let Err(e) = result {
+                        error_count.fetch_add(1, Ordering::Relaxed);
+                        report(&format!("  ✗ Erreur: {}\n", e));
+                        emit_event(
+                            "page_error",
+                            serde_json::json!({ "index": job.index, "url": job.url, "message": e.to_string() }),
+                        );
                     }
+
+                    complete_job(next_links, job.depth + 1);
                 }
-            }
+            });
         }
+    });
+
+    // Remettre les articles dans l'ordre de découverte des URLs
+    let state = state.into_inner().unwrap_or_else(|e| e.into_inner());
+    let saved_count = state.seen_articles.len();
+    if let Some(bar) = &progress {
+        bar.finish_with_message(format!(
+            "{} article(s) sauvegardé(s), {} erreur(s)",
+            saved_count,
+            error_count.load(Ordering::Relaxed)
+        ));
+        set_progress_bar(None);
     }
-
-    // Si rien trouvé, fallback sur l'URL directe
-    if results.is_empty() {
-        results.push(direct_url);
+    let skipped_pages = state.skipped_pages;
+    let interrupted_pages = state.interrupted_pages;
+    let mut articles = state.articles;
+    articles.sort_by_key(|(index, _, _, _)| *index);
+    let mut scraped_articles = Vec::with_capacity(articles.len());
+    let mut saved_paths = Vec::with_capacity(articles.len());
+    let mut requested_urls = Vec::with_capacity(articles.len());
+    for (_, page, path, requested_url) in articles {
+        scraped_articles.push(page);
+        saved_paths.push(path);
+        requested_urls.push(requested_url);
     }
 
-    // Dédupliquer (case-insensitive) tout en préservant l'ordre et tronquer à max_resultats
-    use std::collections::HashSet;
-    let mut seen: HashSet<String> = HashSet::new();
-    let mut unique_results: Vec<String> = Vec::new();
-    for u in results.into_iter() {
-        let mut key = u.to_lowercase();
-        if key.ends_with('/') { key = key.trim_end_matches('/').to_string(); }
-        if !seen.contains(&key) {
-            seen.insert(key);
-            unique_results.push(u);
+    // Générer le récapitulatif et les exports, même pour un seul article,
+    // afin que la structure du dossier ne dépende pas du nombre de pages
+    if !scraped_articles.is_empty() && !args.stdout {
+        generate_search_summary(&scraped_articles, &saved_paths, &search_folder, mot_cle_effectif.as_deref())?;
+        generate_index_html(&scraped_articles, &saved_paths, &search_folder, mot_cle_effectif.as_deref())?;
+
+        // Exporter les métadonnées de tous les articles en CSV (pratique pour un tableur)
+        if args.format.contains(&OutputFormat::Csv) {
+            write_csv(&scraped_articles, &search_folder, args.bom)?;
         }
-        if unique_results.len() >= max_resultats { break; }
+        write_all_json(&scraped_articles, &search_folder)?;
+        write_manifest(&scraped_articles, &saved_paths, &requested_urls, &search_folder, mot_cle_effectif.as_deref())?;
     }
 
-    Ok(unique_results)
-}
-
-fn extract_urls_from_opensearch(json: &str) -> Vec<String> {
-    let mut urls = Vec::new();
-    
-    if let Some(last_bracket) = json.rfind('[') {
-        if let Some(end_bracket) = json[last_bracket..].find(']') {
-            let urls_section = &json[last_bracket + 1..last_bracket + end_bracket];
-            
-            for part in urls_section.split(',') {
-                let trimmed = part.trim().trim_matches('"');
-                if trimmed.starts_with("http") {
-                    urls.push(trimmed.to_string());
-                }
-            }
-        }
+    info!("=== Scraping terminé ===");
+    info!("📂 Résultats disponibles dans: {}", search_folder);
+    info!("📊 {} article(s) traité(s) avec succès", saved_count);
+    if skipped_pages > 0 {
+        info!("⏹ {} page(s) ignorée(s) : limite --max-pages atteinte", skipped_pages);
+    }
+    if STOP_REQUESTED.load(Ordering::SeqCst) {
+        warn!(
+            "⏹ Scraping interrompu : {} page(s) terminée(s), {} page(s) non traitée(s)",
+            saved_count, interrupted_pages
+        );
     }
-    
-    urls
+    emit_event(
+        "run_done",
+        serde_json::json!({
+            "saved": saved_count,
+            "skipped_max_pages": skipped_pages,
+            "interrupted": STOP_REQUESTED.load(Ordering::SeqCst),
+            "skipped_interrupted": interrupted_pages,
+            "output": search_folder,
+        }),
+    );
+
+    Ok(())
 }
 
-fn url_encode(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' | '~' => c.to_string(),
-            ' ' => "_".to_string(),
-            _ => format!("%{:02X}", c as u8),
-        })
+/// Extrait les URLs d'une liste texte : une par ligne, espaces retirés,
+/// lignes vides et commentaires `#` ignorés
+fn parse_url_lines(contenu: &str) -> Vec<String> {
+    contenu
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
         .collect()
 }
 
-/// Fonction pour scraper une page Wikipedia
-fn scrape_wikipedia(url: &str, mot_cle: Option<&str>) -> Result<WikipediaPage, Box<dyn Error>> {
-    let url_parts = parse_url(url)?;
-    let host = &url_parts.0;
-    let path = &url_parts.1;
-
-    let html_content = http_get(host, path)?;
-    let document = Html::parse_document(&html_content);
-
-    // Extraire le titre
-    let title_selector = Selector::parse("h1#firstHeading, h1.firstHeading").unwrap();
-    let title = document
-        .select(&title_selector)
-        .next()
-        .map(|el| el.text().collect::<String>())
-        .unwrap_or_else(|| "Sans titre".to_string());
-
-    // Extraire le résumé avec fallbacks
-    let summary = extract_summary(&document);
-
-    // Extraire les sections
-    let mut sections: Vec<String> = Vec::new();
-    let section_selector1 = Selector::parse(".mw-headline").unwrap();
-    for element in document.select(&section_selector1) {
-        let section_text = element.text().collect::<String>().trim().to_string();
-        if !section_text.is_empty() && section_text.len() > 1 {
-            sections.push(section_text);
+/// Clé de déduplication d'un article : hôte et titre canonique tirés de
+/// l'URL `/wiki/...`, ou titre en minuscules si l'URL n'en contient pas
+fn article_key(page: &WikipediaPage) -> String {
+    let url = canonical_url(&page.url);
+    match url.split_once("/wiki/") {
+        Some((base, title)) if !title.is_empty() => {
+            let host = base.rsplit('/').next().unwrap_or(base);
+            format!("{}/wiki/{}", host.to_lowercase(), title.replace(' ', "_"))
         }
+        _ => format!("title:{}", page.title.to_lowercase()),
     }
+}
 
-    // Extraire les liens internes
-         // Extraire les liens internes (filtrés par mot-clé si fourni)
-        let link_selector = Selector::parse("#mw-content-text a[href^='/wiki/']").unwrap();
-    let keyword_lower_opt = mot_cle.map(|k| k.to_lowercase());
-    let keyword_url_opt = mot_cle.map(|k| k.to_lowercase().replace(' ', "_"));
-
-    let links: Vec<String> = document
-        .select(&link_selector)
-        .filter_map(|el: ElementRef| {
-            let href = el.value().attr("href")?;
-            // Ignorer les liens administratifs / ancrages
-            if href.contains(':') || href.contains('#') {
-                return None;
-            }
-
-            // Si mot-clé fourni, vérifier plusieurs endroits (texte du lien, title, URL)
-            if let Some(ref kw) = keyword_lower_opt {
-                let text = el.text().collect::<String>().to_lowercase();
-                let title_attr = el.value().attr("title").unwrap_or("").to_lowercase();
-                let href_lower = href.to_lowercase();
-                let kw_url = keyword_url_opt.as_deref().unwrap_or("");
-
-                let contains = text.contains(kw)
-                    || title_attr.contains(kw)
-                    || href_lower.contains(kw)
-                    || (!kw_url.is_empty() && href_lower.contains(kw_url));
-
-                // Si le lien lui-même ne contient pas le mot-clé, vérifier le paragraphe ancêtre
-                if !contains {
-                    let parent_p_opt = el.ancestors().find_map(|node| {
-                        if let Some(elem) = ElementRef::wrap(node) {
-                            // comparer le nom local de la balise (ex: "p")
-                            if elem.value().name.local.as_ref() == "p" {
-                                return Some(elem);
-                            }
-                        }
-                        None
-                    });
-
-                    if let Some(parent_p) = parent_p_opt {
-                        let parent_text = parent_p.text().collect::<String>().to_lowercase();
-                        if parent_text.contains(kw) {
-                            return Some(format!("https://fr.wikipedia.org{}", href));
-                        }
-                    }
-
-                    return None;
-                }
-            }
-
-            Some(format!("https://fr.wikipedia.org{}", href))
-        })
-        .collect();
- 
-
-
-    // Extraire les images (filtrer les icônes)
-    let image_selector = Selector::parse("img[src]").unwrap();
-    let images: Vec<String> = document
-        .select(&image_selector)
-        .filter_map(|el| {
-            let src = el.value().attr("src")?;
-            let width = el.value().attr("width");
-            let height = el.value().attr("height");
-            
-            if let (Some(w), Some(h)) = (width, height) {
-                if let (Ok(w_num), Ok(h_num)) = (w.parse::<u32>(), h.parse::<u32>()) {
-                    if w_num < 100 || h_num < 100 {
-                        return None;
-                    }
-                }
-            }
-            
-            if !(src.starts_with("//") || src.starts_with("http")) {
-                return None;
-            }
-            
-            if !(src.contains(".jpg") || src.contains(".jpeg") || 
-                 src.contains(".png") || src.contains(".svg") || src.contains(".gif")) {
-                return None;
-            }
-            
-            if src.contains("/static/images/") || src.contains("/icons/") ||
-               src.contains("Icon_") || src.contains("icon") || src.contains("logo") ||
-               src.contains("20px-") || src.contains("15px-") {
-                return None;
-            }
-            
-            let img_url = if src.starts_with("//") {
-                format!("https:{}", src)
-            } else {
-                src.to_string()
-            };
-            
-            if img_url.contains("upload.wikimedia.org") {
-                Some(img_url)
-            } else {
-                None
-            }
-        })
-        .take(20)
-        .collect();
-
-    Ok(WikipediaPage {
-        url: url.to_string(),
-        title,
-        summary,
-        sections,
-        links,
-        images,
-    })
-}
-
-fn extract_summary(document: &Html) -> String {
-    // On cible le conteneur principal du contenu de l'article.
-    if let Some(container) = document.select(&Selector::parse("div.mw-parser-output").unwrap()).next() {
-        let mut summary_parts: Vec<String> = Vec::new();
-        let h2_selector = Selector::parse("h2").unwrap();
-
-        // On parcourt tous les nœuds enfants directs du conteneur.
-        for node in container.children() {
-            if let Some(elem) = ElementRef::wrap(node) {
-                let tag_name = elem.value().name.local.as_ref();
-
-                // C'est le marqueur de la fin du résumé.
-                // On arrête si l'élément est un <h2> ou s'il contient un <h2>.
-                if tag_name == "h2" || elem.select(&h2_selector).next().is_some() {
-                    break;
-                }
-
-                // On ne garde que le texte des balises <p>.
-                if tag_name == "p" {
-                    let paragraph_text = elem.text().collect::<String>().trim().to_string();
-                    
-                    // On s'assure que le paragraphe n'est pas vide.
-                    if !paragraph_text.is_empty() {
-                        summary_parts.push(paragraph_text);
-                    }
-                }
+/// Vérifie les fichiers JSON donnés à `--validate` ; échoue si l'un d'eux n'est pas valide
+fn validate_files(files: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut invalid = 0;
+    for file in files {
+        // Le fichier désigné est lu tel quel, décompressé s'il porte l'extension `.gz`
+        let content = if file.ends_with(".gz") { read_gzip_file(file) } else { fs::read_to_string(file) };
+        let content = content.map_err(ScraperError::from);
+        match content.and_then(|content| validate_pages_json(&content)) {
+            Ok(count) => println!("✓ {} : {} article(s) valide(s)", file, count),
+            Err(e) => {
+                invalid += 1;
+                eprintln!("✗ {} : {}", file, e);
             }
         }
-
-        // On assemble les paragraphes collectés.
-        if !summary_parts.is_empty() {
-            return summary_parts.join("\n\n");
-        }
     }
-
-    // Fallback si aucun résumé n'est trouvé.
-    String::new()
+    if invalid > 0 {
+        return Err(format!("{} fichier(s) invalide(s) sur {}", invalid, files.len()).into());
+    }
+    Ok(())
 }
 
-fn http_get(host: &str, path: &str) -> Result<String, Box<dyn Error>> {
-    if path.contains("wikipedia.org") || host.contains("wikipedia") {
-        https_get(host, path)
+/// Chemin de sauvegarde d'un article retrouvé par [`resume_index`] au chemin `base`,
+/// tel qu'il figure dans le résumé et le manifeste
+fn saved_path(base: &str, output: &OutputContext) -> String {
+    if output.keyword_mode && output.formats.contains(&OutputFormat::Markdown) {
+        format!("{}.md", base)
     } else {
-        https_get(host, path)
+        base.to_string()
     }
 }
 
-fn https_get(host: &str, path: &str) -> Result<String, Box<dyn Error>> {
-    let mut root_store = rustls::RootCertStore::empty();
-    root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-
-    let config = rustls::ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
+/// Reprend un article déjà sauvegardé dans les résultats, sans réécrire ses fichiers,
+/// pour qu'il figure dans le résumé et le manifeste régénérés
+fn keep_saved_article(
+    index: usize,
+    requested_url: &str,
+    page_data: WikipediaPage,
+    saved_path: String,
+    state: &Mutex<RunState>,
+) {
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+    state.seen_articles.insert(article_key(&page_data), page_data.url.clone());
+    state.articles.push((index, page_data, saved_path, requested_url.to_string()));
+}
 
-    let server_name = ServerName::try_from(host)?;
-    let mut conn = rustls::ClientConnection::new(Arc::new(config), server_name.to_owned())?;
+/// Déduplique puis sauvegarde un article scrapé, et l'ajoute aux résultats
+///
+/// La sauvegarde se fait sous le verrou de l'état partagé pour que les noms de
+/// fichiers et la déduplication restent cohérents entre workers ; seul le
+/// téléchargement des images se fait hors verrou. `resume_base` est l'emplacement
+/// d'un article retrouvé sans ses données par `--resume`, réécrit en place.
+fn store_article(
+    index: usize,
+    requested_url: &str,
+    resume_base: Option<&str>,
+    page_data: WikipediaPage,
+    state: &Mutex<RunState>,
+    output: &OutputContext,
+) -> Result<(), Box<dyn Error>> {
+    let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Déduplication par URL canonique (après redirections), ou par titre à défaut
+    let dedup_key = article_key(&page_data);
+    if let Some(first_url) = state.seen_articles.get(&dedup_key) {
+        info!(
+            "  ⚠ Article déjà traité : {} — même article que {} — ignoré\n",
+            page_data.title, first_url
+        );
+        emit_event(
+            "page_skipped",
+            serde_json::json!({ "index": index, "url": page_data.url, "reason": "duplicate" }),
+        );
+        return Ok(());
+    }
 
-    let addr = format!("{}:443", host);
-    let mut sock = TcpStream::connect(&addr)
-        .map_err(|e| format!("Connexion impossible à {}: {}", host, e))?;
+    // D'autres workers ont pu atteindre la limite pendant le scraping de cette page
+    if state.page_limit_reached(output.max_pages) {
+        state.skipped_pages += 1;
+        info!("  ⏹ Limite --max-pages atteinte — {} non sauvegardé\n", page_data.title);
+        emit_event(
+            "page_skipped",
+            serde_json::json!({ "index": index, "url": page_data.url, "reason": "max_pages" }),
+        );
+        return Ok(());
+    }
 
-    let request = format!(
-        "GET {} HTTP/1.1\r\n\
-         Host: {}\r\n\
-         User-Agent: Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36\r\n\
-         Accept: text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8\r\n\
-         Accept-Language: fr,fr-FR;q=0.8,en-US;q=0.5,en;q=0.3\r\n\
-         Connection: close\r\n\
-         \r\n",
-        path, host
-    );
+    let base_name = output.file_name(index, &page_data.title);
+    // Fichiers par article : chemin choisi selon --on-conflict si le nom est déjà pris
+    let output_path = if output.stdout || state.ndjson.is_some() {
+        String::new()
+    } else if let Some(base) = resume_base {
+        base.to_string()
+    } else {
+        match output.output_path(&base_name) {
+            Some(path) => path,
+            None => {
+                info!("  ⏭ {} existe déjà — {} ignoré (--on-conflict skip)\n", base_name, page_data.title);
+                emit_event(
+                    "page_skipped",
+                    serde_json::json!({ "index": index, "url": page_data.url, "reason": "exists" }),
+                );
+                return Ok(());
+            }
+        }
+    };
 
-    while conn.is_handshaking() {
-        conn.complete_io(&mut sock)?;
+    if let Some(db) = &state.database {
+        save_page_to_db(db, &page_data)?;
     }
 
-    conn.writer().write_all(request.as_bytes())?;
-    conn.complete_io(&mut sock)?;
-
-    let mut response = Vec::new();
-    loop {
-        let mut buf = vec![0u8; 8192];
-        match conn.reader().read(&mut buf) {
-            Ok(0) => break,
-            Ok(n) => {
-                response.extend_from_slice(&buf[..n]);
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                conn.complete_io(&mut sock)?;
-            }
-            Err(e) => return Err(e.into()),
+    // Section demandée par --section (sa présence a été vérifiée avant la sauvegarde)
+    let section = output.section.and_then(|name| find_section(&page_data, name));
+    let (saved_path, images_folder) = if output.stdout {
+        // Articles séparés par une règle horizontale Markdown
+        let mut stdout = io::stdout().lock();
+        if !state.seen_articles.is_empty() {
+            writeln!(stdout, "\n---\n")?;
         }
-        
-        if let Err(e) = conn.complete_io(&mut sock) {
-            if e.kind() != std::io::ErrorKind::WouldBlock {
-                break;
-            }
+        let markdown = match section {
+            Some(section) => generate_section_markdown(&page_data, section),
+            None => generate_markdown(&page_data, &output.markdown),
+        };
+        write!(stdout, "{}", markdown)?;
+        stdout.flush()?;
+        ("stdout".to_string(), String::new())
+    } else if let Some(ndjson) = state.ndjson.as_mut() {
+        // Une ligne JSON par article, vidée immédiatement pour les consommateurs en aval
+        serde_json::to_writer(&mut *ndjson, &page_data)?;
+        ndjson.write_all(b"\n")?;
+        ndjson.flush()?;
+
+        let ndjson_path = format!("{}/articles.ndjson", output.search_folder);
+        let images_folder = format!("{}/images/{}", output.search_folder, base_name);
+        (ndjson_path, images_folder)
+    } else if output.keyword_mode {
+        // Recherche par mot-clé (CLI ou interactif) : fichiers à plat à la racine du dossier
+        let stem = output_path;
+        save_page_data(
+            &page_data,
+            PageLocation::Flat(&stem),
+            output.formats,
+            &output.markdown,
+            output.bom,
+            output.gzip,
+        )?;
+        if let Some(section) = section {
+            save_section(&page_data, section, PageLocation::Flat(&stem), output.bom, output.gzip)?;
         }
-    }
-    
-    let response_str = String::from_utf8_lossy(&response).to_string();
 
-    let status_line = response_str.lines().next().unwrap_or("");
-    
-    if status_line.contains("301") || status_line.contains("302") {
-        if let Some(location) = extract_header(&response_str, "Location") {
-            if let Ok((new_host, new_path)) = parse_url(&location) {
-                return https_get(&new_host, &new_path);
-            }
+        let file_name = stem.rsplit('/').next().unwrap_or(&base_name);
+        let images_folder = format!("{}/images/{}", output.search_folder, file_name);
+        let saved_path = if output.formats.contains(&OutputFormat::Markdown) {
+            format!("{}.md", stem)
+        } else {
+            stem
+        };
+        (saved_path, images_folder)
+    } else {
+        // Un dossier par page, avec un fichier par format demandé
+        let page_folder = output_path;
+        fs::create_dir_all(&page_folder)?;
+
+        save_page_data(
+            &page_data,
+            PageLocation::Folder(&page_folder),
+            output.formats,
+            &output.markdown,
+            output.bom,
+            output.gzip,
+        )?;
+        if let Some(section) = section {
+            save_section(&page_data, section, PageLocation::Folder(&page_folder), output.bom, output.gzip)?;
         }
-    }
 
-    if !status_line.contains("200") {
-        return Err(format!("Erreur HTTP: {}", status_line).into());
-    }
+        let images_folder = format!("{}/images", page_folder);
+        (page_folder, images_folder)
+    };
 
-    if let Some(body_start) = response_str.find("\r\n\r\n") {
-        Ok(response_str[body_start + 4..].to_string())
-    } else if let Some(body_start) = response_str.find("\n\n") {
-        Ok(response_str[body_start + 2..].to_string())
-    } else {
-        Err("Impossible de séparer headers et body".into())
-    }
-}
+    info!("  ✓ Titre: {}", page_data.title);
+    info!("  ✓ Sections: {}", page_data.sections.len());
+    info!("  ✓ Liens: {}", page_data.links.len());
+    info!("  ✓ Images: {}", page_data.images.len());
+    info!("  ✓ Sauvegardé dans: {}\n", saved_path);
+    emit_event(
+        "page_done",
+        serde_json::json!({
+            "index": index,
+            "url": page_data.url,
+            "title": page_data.title,
+            "sections": page_data.sections.len(),
+            "links": page_data.links.len(),
+            "images": page_data.images.len(),
+            "path": saved_path,
+        }),
+    );
 
-fn extract_header(response: &str, header_name: &str) -> Option<String> {
-    let header_prefix = format!("{}: ", header_name);
-    
-    for line in response.lines() {
-        if line.starts_with(&header_prefix) || line.to_lowercase().starts_with(&header_prefix.to_lowercase()) {
-            return Some(line[header_prefix.len()..].trim().to_string());
-        }
+    // Ajouter à la liste pour le résumé global (sauf en NDJSON, déjà écrit sur disque,
+    // et avec --stdout, sans résumé)
+    let images: Vec<String> = page_data.images.iter().map(|image| image.url.clone()).collect();
+    state.seen_articles.insert(dedup_key, page_data.url.clone());
+    if state.ndjson.is_none() && !output.stdout {
+        state.articles.push((index, page_data, saved_path, requested_url.to_string()));
     }
-    
-    None
-}
+    drop(state);
 
-fn parse_url(url: &str) -> Result<(String, String), Box<dyn Error>> {
-    let url = url.trim();
+    if output.download_images {
+        download_images(&images, &images_folder, output.http_config)?;
+    }
 
-    let url = url
-        .strip_prefix("https://")
-        .or_else(|| url.strip_prefix("http://"))
-        .unwrap_or(url);
+    Ok(())
+}
 
-    if let Some(pos) = url.find('/') {
-        let host = url[..pos].to_string();
-        let path = url[pos..].to_string();
-        Ok((host, path))
-    } else {
-        Ok((url.to_string(), "/".to_string()))
+/// Demande à l'utilisateur quel article d'une page d'homonymie scraper ;
+/// `None` conserve la page d'homonymie elle-même
+fn choisir_homonyme(page: &WikipediaPage) -> Result<Option<String>, Box<dyn Error>> {
+    println!("\n  « {} » est une page d'homonymie. Articles proposés :", page.title);
+    for (i, option) in page.disambiguation_options.iter().enumerate() {
+        println!("    {}. {}", i + 1, option);
     }
-}
 
-/// Fonction pour sauvegarder les données d'une page
-fn save_page_data(page: &WikipediaPage, folder: &str) -> Result<(), Box<dyn Error>> {
-    let json_path = format!("{}/data.json", folder);
-    let json = serde_json::to_string_pretty(page)?;
-    fs::write(&json_path, json)?;
+    print!("  Numéro de l'article à scraper (Entrée pour garder la page d'homonymie) : ");
+    io::stdout().flush()?;
 
-    let markdown_path = format!("{}/article.md", folder);
-    let markdown_content = generate_markdown(page);
-    fs::write(&markdown_path, markdown_content)?;
+    let mut choix = String::new();
+    io::stdin().read_line(&mut choix)?;
 
-    let summary_path = format!("{}/resume.txt", folder);
-    let summary_content = format!(
-        "Titre: {}\n\nURL: {}\n\nRésumé:\n{}\n",
-        page.title, page.url, page.summary
-    );
-    fs::write(&summary_path, summary_content)?;
+    Ok(choix
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| page.disambiguation_options.get(i))
+        .cloned())
+}
 
-    let sections_path = format!("{}/sections.txt", folder);
-    let sections_content = page.sections.join("\n");
-    fs::write(&sections_path, sections_content)?;
+/// Interprète une sélection de résultats (« 1,3,5 », « 2-4 », « all » ou vide pour
+/// tous) et renvoie les indices (base 0) dans l'ordre saisi, sans doublon
+fn parse_selection(saisie: &str, count: usize) -> Result<Vec<usize>, String> {
+    let saisie = saisie.trim();
+    if saisie.is_empty() || saisie.eq_ignore_ascii_case("all") || saisie.eq_ignore_ascii_case("tous") {
+        return Ok((0..count).collect());
+    }
 
-    let links_path = format!("{}/liens.txt", folder);
-    let links_content = page.links.join("\n");
-    fs::write(&links_path, links_content)?;
+    let parse_index = |s: &str| -> Result<usize, String> {
+        match s.trim().parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+            _ => Err(format!("« {} » n'est pas un numéro entre 1 et {}", s.trim(), count)),
+        }
+    };
 
-    let images_path = format!("{}/images.txt", folder);
-    let images_content = page.images.join("\n");
-    fs::write(&images_path, images_content)?;
+    let mut selection = Vec::new();
+    for part in saisie.split(',').filter(|part| !part.trim().is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse_index(start)?, parse_index(end)?),
+            None => {
+                let index = parse_index(part)?;
+                (index, index)
+            }
+        };
+        if start > end {
+            return Err(format!("Intervalle « {} » invalide", part.trim()));
+        }
+        for index in start..=end {
+            if !selection.contains(&index) {
+                selection.push(index);
+            }
+        }
+    }
 
-    Ok(())
+    if selection.is_empty() {
+        return Err("Aucun article sélectionné".to_string());
+    }
+    Ok(selection)
 }
 
-fn generate_markdown(page: &WikipediaPage) -> String {
-    let mut markdown = String::new();
-    
-    markdown.push_str(&format!("# {}\n\n", page.title));
-    markdown.push_str(&format!("**Source:** [Wikipedia]({})  \n", page.url));
-    markdown.push_str(&format!("**Date:** {}  \n\n", 
-        chrono::Local::now().format("%d/%m/%Y à %H:%M:%S")));
-    
-    markdown.push_str("## Résumé\n\n");
-    if !page.summary.is_empty() {
-        markdown.push_str(&page.summary);
-        markdown.push_str("\n\n");
-    } else {
-        markdown.push_str("*Résumé non disponible*\n\n");
-    }
-    
-    if !page.sections.is_empty() {
-        markdown.push_str("## Sections\n\n");
-        for section in &page.sections {
-            markdown.push_str(&format!("- {}\n", section));
-        }
-        markdown.push_str("\n");
+/// Affiche une invite et lit une ligne sur l'entrée standard (sans le retour à la ligne)
+///
+/// Une entrée standard fermée (Ctrl+D) est une erreur : les menus ne peuvent plus avancer.
+fn lire_ligne(invite: &str) -> Result<String, Box<dyn Error>> {
+    print!("{}", invite);
+    io::stdout().flush()?;
+
+    let mut ligne = String::new();
+    if io::stdin().read_line(&mut ligne)? == 0 {
+        return Err("Entrée standard fermée".into());
     }
-    
-    markdown
+    Ok(ligne.trim().to_string())
 }
 
 /// Fonction pour le mode interactif (saisie des URLs par l'utilisateur)
-fn get_urls_interactif(default_nombre: usize) -> Result<(Vec<String>, Option<String>), Box<dyn Error>> {
-    println!("\n=== Scraper Wikipedia (Mode interactif) ===\n");
-    println!("Choisissez une option :");
-    println!("1. Entrer des URLs directement");
-    println!("2. Rechercher par mot-clé");
-    
-    print!("\nVotre choix (1-2) : ");
-    io::stdout().flush()?;
-    
-    let mut choix = String::new();
-    io::stdin().read_line(&mut choix)?;
-    
-    match choix.trim() {
-        "1" => {
-            println!("\nEntrez les URLs Wikipedia (une par ligne)");
-            println!("Appuyez sur Ctrl+D (Linux/Mac) ou Ctrl+Z puis Entrée (Windows) pour terminer\n");
-
-            let mut urls = Vec::new();
-            
-            loop {
-                let mut url = String::new();
-                match io::stdin().read_line(&mut url) {
-                    Ok(0) => break, // EOF (Ctrl+D ou Ctrl+Z)
-                    Ok(_) => {
-                        let url = url.trim();
-                        if !url.is_empty() {
-                            urls.push(url.to_string());
-                            println!("  [{}] Ajouté: {}", urls.len(), url);
+///
+/// Le menu principal est réaffiché après un choix invalide ; après une recherche,
+/// l'utilisateur peut relancer avec un autre terme ou un autre nombre de résultats
+/// avant de scraper.
+fn get_urls_interactif(
+    default_nombre: usize,
+    lang: &str,
+    use_api: bool,
+    exact: bool,
+    include_namespaces: &[String],
+    config: &HttpConfig,
+) -> Result<(Vec<String>, Option<String>), Box<dyn Error>> {
+    println!("\n=== Scraper Wikipedia (Mode interactif) ===");
+
+    loop {
+        println!("\nChoisissez une option :");
+        println!("1. Entrer des URLs directement");
+        println!("2. Rechercher par mot-clé");
+
+        let choix = lire_ligne("\nVotre choix (1-2) : ")?;
+        match choix.as_str() {
+            "1" => {
+                println!("\nEntrez les URLs Wikipedia (une par ligne)");
+                println!("Appuyez sur Ctrl+D (Linux/Mac) ou Ctrl+Z puis Entrée (Windows) pour terminer\n");
+
+                let mut urls = Vec::new();
+
+                loop {
+                    let mut url = String::new();
+                    match io::stdin().read_line(&mut url) {
+                        Ok(0) => break, // EOF (Ctrl+D ou Ctrl+Z)
+                        Ok(_) => {
+                            let url = url.trim();
+                            if !url.is_empty() {
+                                urls.push(url.to_string());
+                                println!("  [{}] Ajouté: {}", urls.len(), url);
+                            }
                         }
+                        Err(_) => break,
                     }
-                    Err(_) => break,
                 }
+
+                return Ok((urls, None));
             }
-            
-            Ok((urls, None))
-        }
-        "2" => {
-            print!("Entrez le mot-clé à rechercher : ");
-            io::stdout().flush()?;
-            
-            let mut mot_cle = String::new();
-            io::stdin().read_line(&mut mot_cle)?;
-            let mot_cle = mot_cle.trim();
-            
-            print!("Nombre de résultats à scraper (défaut: {}, max 20) : ", default_nombre);
-            io::stdout().flush()?;
-            
-            let mut nombre_str = String::new();
-            io::stdin().read_line(&mut nombre_str)?;
-            
-            let nombre = if nombre_str.trim().is_empty() {
-                default_nombre
-            } else {
-                nombre_str.trim().parse::<usize>().unwrap_or(default_nombre).min(20)
-            };
-            
-            println!("\n🔍 Recherche en cours de \"{}\" ({} résultats)...\n", mot_cle, nombre);
-            let results = rechercher_wikipedia(mot_cle, nombre)?;
-            Ok((results, Some(mot_cle.to_string())))
-        }
-        _ => {
-            println!("Choix invalide");
-            Ok((Vec::new(), None))
+            "2" => {
+                if let IssueRecherche::Scraper { urls, mot_cle } =
+                    recherche_interactive(default_nombre, lang, use_api, exact, include_namespaces, config)?
+                {
+                    return Ok((urls, Some(mot_cle)));
+                }
+            }
+            _ => println!("  ✗ Choix invalide « {} » : tapez 1 ou 2", choix),
         }
     }
 }
 
-/// Fonction pour générer un résumé de la recherche
-fn generate_search_summary(
-    articles: &[WikipediaPage], 
-    folder: &str, 
-    search_term: Option<&str>
-) -> Result<(), Box<dyn Error>> {
-    let summary_path = format!("{}/RESUME_RECHERCHE.md", folder);
-    let mut summary = String::new();
-    
-    // En-tête
-    if let Some(term) = search_term {
-        summary.push_str(&format!("# 🔍 Résumé de recherche : \"{}\"\n\n", term));
-    } else {
-        summary.push_str("# 📚 Résumé de scraping\n\n");
-    }
-    
-    summary.push_str(&format!("**Date** : {}\n\n", 
-        chrono::Local::now().format("%d/%m/%Y à %H:%M:%S")));
-    summary.push_str(&format!("**Nombre d'articles** : {}\n\n", articles.len()));
-    
-    summary.push_str("---\n\n");
-    
-    // Table des matières
-    summary.push_str("## 📋 Articles scrapés\n\n");
-    summary.push_str("| # | Article | Sections | Liens | Images | Dossier |\n");
-    summary.push_str("|---|---------|----------|-------|--------|----------|\n");
-    
-    for (i, article) in articles.iter().enumerate() {
-        let folder_name = sanitize(&article.title);
-        // Si la recherche est par mot-clé, les fichiers markdown sont à la racine du dossier de recherche
-        let table_link = if search_term.is_some() {
-            format!("./{}.md", folder_name)
-        } else {
-            format!("./{}/article.md", folder_name)
-        };
+/// Issue de la recherche interactive
+enum IssueRecherche {
+    /// Résultats retenus pour le scraping
+    Scraper { urls: Vec<String>, mot_cle: String },
+    /// Retour au menu principal
+    Menu,
+}
 
-        let table_icon = if search_term.is_some() { "📄" } else { "📁" };
-
-        summary.push_str(&format!(
-            "| {} | [{}]({}) | {} | {} | {} | [{}]({}) |\n",
-            i + 1,
-            article.title,
-            article.url,
-            article.sections.len(),
-            article.links.len(),
-            article.images.len(),
-            table_icon,
-            table_link
-        ));
-    }
-    
-    summary.push_str("\n---\n\n");
-    
-    // Résumés courts de chaque article
-    summary.push_str("## 📖 Résumés des articles\n\n");
-    
-    for (i, article) in articles.iter().enumerate() {
-        summary.push_str(&format!("### {}. {}\n\n", i + 1, article.title));
-        summary.push_str(&format!("**URL** : [{}]({})\n\n", article.title, article.url));
-        
-            if !article.summary.is_empty() {
-                // Prendre les 300 premiers caractères du résumé en respectant les frontières de caractères Unicode
-                let short_summary = if article.summary.chars().count() > 300 {
-                    let mut s: String = article.summary.chars().take(300).collect();
-                    s.push_str("...");
-                    s
-                } else {
-                    article.summary.clone()
-                };
-                summary.push_str(&format!("{}\n\n", short_summary));
-            // Lien vers le markdown : soit ./<title>.md (mode mot-clé), soit ./<title>/article.md
-            if search_term.is_some() {
-                summary.push_str(&format!("> 📄 [Lire l'article complet](./{}.md)\n\n", sanitize(&article.title)));
-            } else {
-                summary.push_str(&format!("> 📄 [Lire l'article complet](./{}/article.md)\n\n", sanitize(&article.title)));
-            }
+/// Recherche par mot-clé du mode interactif, relançable jusqu'à ce que
+/// l'utilisateur choisisse de scraper ou revienne au menu principal
+fn recherche_interactive(
+    default_nombre: usize,
+    lang: &str,
+    use_api: bool,
+    exact: bool,
+    include_namespaces: &[String],
+    config: &HttpConfig,
+) -> Result<IssueRecherche, Box<dyn Error>> {
+    let mut mot_cle = lire_ligne("Entrez le mot-clé à rechercher : ")?;
+    let mut nombre = lire_nombre(default_nombre)?;
+
+    loop {
+        info!("\n🔍 Recherche en cours de \"{}\" ({} résultats)...\n", mot_cle, nombre);
+        // Une recherche en échec n'arrête pas le programme : on peut relancer ou revenir au menu
+        let results = search_wikipedia(&mot_cle, nombre, lang, use_api, exact, include_namespaces, config)
+            .unwrap_or_else(|e| {
+                eprintln!("  ✗ Erreur: {}", e);
+                Vec::new()
+            });
+
+        if results.is_empty() {
+            println!("Aucun résultat trouvé pour \"{}\"", mot_cle);
         } else {
-            summary.push_str("*Résumé non disponible*\n\n");
-            if search_term.is_some() {
-                summary.push_str(&format!("> 📄 [Consulter les données](./{}.md)\n\n", sanitize(&article.title)));
-            } else {
-                summary.push_str(&format!("> 📄 [Consulter les données](./{}/)\n\n", sanitize(&article.title)));
+            println!("✓ {} résultat(s) trouvé(s) :\n", results.len());
+            for (i, url) in results.iter().enumerate() {
+                println!("  {}. {}", i + 1, url);
             }
         }
-    
-        // Sections principales
-        if !article.sections.is_empty() {
-            summary.push_str("**Sections principales** : ");
-            let sections_preview: Vec<String> = article.sections.iter().take(5).cloned().collect();
-            summary.push_str(&sections_preview.join(", "));
-            if article.sections.len() > 5 {
-                summary.push_str(&format!(" (et {} autres...)", article.sections.len() - 5));
+
+        println!("\n[Entrée] Scraper   [r] Nouvelle recherche   [n] Changer le nombre   [m] Menu principal");
+        // Redemander le choix tant qu'il est invalide, sans relancer la recherche
+        loop {
+            let choix = lire_ligne("Votre choix : ")?.to_lowercase();
+            match choix.as_str() {
+                "" if !results.is_empty() => {
+                    // Ne garder que les résultats choisis par l'utilisateur
+                    let selection = loop {
+                        let saisie = lire_ligne(
+                            "\nArticles à scraper (ex : 1,3,5 ou 2-4, « all » ou Entrée pour tous) : ",
+                        )?;
+                        match parse_selection(&saisie, results.len()) {
+                            Ok(selection) => break selection,
+                            Err(e) => println!("  ✗ {}", e),
+                        }
+                    };
+                    let results = selection.into_iter().map(|i| results[i].clone()).collect();
+                    return Ok(IssueRecherche::Scraper { urls: results, mot_cle });
+                }
+                "r" => {
+                    mot_cle = lire_ligne("Entrez le mot-clé à rechercher : ")?;
+                    nombre = lire_nombre(default_nombre)?;
+                    break;
+                }
+                "n" => {
+                    nombre = lire_nombre(default_nombre)?;
+                    break;
+                }
+                "m" => return Ok(IssueRecherche::Menu),
+                "" => println!("  ✗ Aucun résultat à scraper : tapez r, n ou m"),
+                _ => println!("  ✗ Choix invalide « {} » : tapez Entrée, r, n ou m", choix),
             }
-            summary.push_str("\n\n");
         }
-        
-        summary.push_str("---\n\n");
-    }
-    
-    // Statistiques globales
-    summary.push_str("## 📊 Statistiques globales\n\n");
-    summary.push_str("```\n");
-    summary.push_str(&format!("Total articles       : {}\n", articles.len()));
-    summary.push_str(&format!("Total sections       : {}\n", articles.iter().map(|a| a.sections.len()).sum::<usize>()));
-    summary.push_str(&format!("Total liens          : {}\n", articles.iter().map(|a| a.links.len()).sum::<usize>()));
-    summary.push_str(&format!("Total images         : {}\n", articles.iter().map(|a| a.images.len()).sum::<usize>()));
-    
-    let avg_sections = articles.iter().map(|a| a.sections.len()).sum::<usize>() as f64 / articles.len() as f64;
-    summary.push_str(&format!("Moyenne sections     : {:.1}\n", avg_sections));
-    
-    let total_chars: usize = articles.iter().map(|a| a.summary.len()).sum();
-    summary.push_str(&format!("Total caractères     : {}\n", total_chars));
-    summary.push_str("```\n\n");
-    
-    // Footer
-    summary.push_str("---\n\n");
-    summary.push_str("*Résumé généré automatiquement par le Scrappeur Wikipedia en Rust*\n");
-    summary.push_str("*ESGI - BAC +4 RUST*\n");
-    
-    fs::write(&summary_path, summary)?;
-    println!("\n📄 Résumé de recherche généré : {}", summary_path);
-    
-    Ok(())
-}
\ No newline at end of file
+    }
+}
+
+/// Nombre maximal de résultats proposé en mode interactif
+const MAX_RESULTATS_INTERACTIF: usize = 20;
+
+/// Demande le nombre de résultats à scraper, jusqu'à obtenir un entier entre 1 et
+/// `MAX_RESULTATS_INTERACTIF` (Entrée garde la valeur par défaut)
+fn lire_nombre(default_nombre: usize) -> Result<usize, Box<dyn Error>> {
+    loop {
+        let saisie = lire_ligne(&format!(
+            "Nombre de résultats à scraper (défaut: {}, max {}) : ",
+            default_nombre, MAX_RESULTATS_INTERACTIF
+        ))?;
+        if saisie.is_empty() {
+            return Ok(default_nombre);
+        }
+        match saisie.parse::<usize>() {
+            Ok(nombre) if (1..=MAX_RESULTATS_INTERACTIF).contains(&nombre) => return Ok(nombre),
+            _ => println!(
+                "  ✗ « {} » n'est pas un nombre entre 1 et {}",
+                saisie, MAX_RESULTATS_INTERACTIF
+            ),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Noms de fichiers des articles produits par `safe_file_name`
+
+use wikipedia_scraper::safe_file_name;
+
+#[test]
+fn titre_ordinaire() {
+    assert_eq!(safe_file_name("Paris", 1), "Paris");
+    // Les séparateurs de chemin sont retirés : pas de sortie du dossier de recherche
+    assert_eq!(safe_file_name("../../etc/passwd", 2), "....etcpasswd");
+}
+
+#[test]
+fn nom_vide_ou_fait_de_points() {
+    assert_eq!(safe_file_name("", 3), "article_3");
+    assert_eq!(safe_file_name("..", 4), "article_4");
+    assert_eq!(safe_file_name("/", 5), "article_5");
+    assert_eq!(safe_file_name(". . .", 6), "article_6");
+}
+
+#[test]
+fn noms_reserves_windows() {
+    assert_eq!(safe_file_name("CON", 7), "article_7");
+    assert_eq!(safe_file_name("nul.md", 8), "article_8");
+    assert_eq!(safe_file_name("Com1 ", 9), "article_9");
+    // Un nom qui ne fait que commencer par un nom réservé est conservé
+    assert_eq!(safe_file_name("Console", 10), "Console");
+}
+
+#[test]
+fn longueur_limitee() {
+    let name = safe_file_name(&"é".repeat(300), 11);
+
+    // Coupé sur une frontière de caractère, sous la limite des systèmes de fichiers
+    assert_eq!(name, "é".repeat(100));
+}
@@ -0,0 +1,289 @@
+//! Extraction des articles à partir de pages Wikipedia enregistrées
+//! (`tests/fixtures/`), sans accès réseau
+
+use wikipedia_scraper::{find_section, parse_wikipedia_html, ScrapeOptions, WikipediaPage};
+
+const AVION: &str = include_str!("fixtures/avion.html");
+const HOMONYMIE: &str = include_str!("fixtures/homonymie.html");
+const SPECIAL: &str = include_str!("fixtures/special.html");
+const SOUS_TITRE: &str = include_str!("fixtures/sous_titre.html");
+
+fn parse(html: &str, url: &str) -> WikipediaPage {
+    parse_wikipedia_html(html, url, None, "fr", &ScrapeOptions::default())
+}
+
+#[test]
+fn titre_et_resume() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    assert_eq!(page.url, "https://fr.wikipedia.org/wiki/Avion");
+    assert_eq!(page.title, "Avion");
+    assert_eq!(
+        page.lead,
+        "Un avion est un aéronef de plus lourd que l'air, entraîné par un organe moteur \
+         et dont la sustentation est assurée par des voilures fixes."
+    );
+    // Les deux paragraphes d'introduction, sans le paragraphe vide ni les appels de note
+    assert_eq!(
+        page.summary,
+        format!("{}\n\nLe premier vol motorisé est attribué aux frères Wright en 1903.", page.lead)
+    );
+    assert!(!page.disambiguation, "le bandeau d'homonymie en tête d'article n'en fait pas une page d'homonymie");
+}
+
+#[test]
+fn sections_et_niveaux() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    let titles: Vec<(&str, u8)> = page.sections.iter().map(|s| (s.title.as_str(), s.level)).collect();
+    assert_eq!(
+        titles,
+        [
+            ("Histoire", 2),
+            ("Premiers vols", 3),
+            ("Conception", 2),
+            ("Notes et références", 2),
+            ("Liens externes", 2),
+        ]
+    );
+    assert_eq!(page.sections[0].body, "Les pionniers de l'aviation ont multiplié les essais au XIXe siècle.");
+    // Le bandeau de portails n'est pas du texte de section
+    assert_eq!(page.sections[2].body, "Un avion comporte un fuselage, une voilure et un empennage.");
+}
+
+#[test]
+fn section_recherchee_par_titre() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    // Ni la casse ni les accents ne comptent ; le texte s'arrête au titre suivant
+    let section = find_section(&page, "premiers VOLS").expect("section « Premiers vols »");
+    assert_eq!(section.body, "En 1890, Clément Ader décolle à bord de l'Éole.");
+    assert_eq!(find_section(&page, "Notes et references").map(|s| s.level), Some(2));
+    assert!(find_section(&page, "Galerie").is_none());
+}
+
+#[test]
+fn introduction_non_repetee_dans_les_sections() {
+    let page = parse(SOUS_TITRE, "https://fr.wikipedia.org/wiki/Cheval");
+
+    // Le sous-titre ne coupe pas l'introduction : son paragraphe est dans le résumé...
+    assert_eq!(
+        page.summary,
+        "Le cheval est un grand mammifère herbivore. Il vit en troupeau.\n\nIl est domestiqué depuis\n l'Antiquité."
+    );
+    // ... et n'est donc pas répété dans la première section, malgré le retour à la ligne.
+    // Le paragraphe placé après le sommaire n'est qu'un extrait du résumé : il est conservé
+    assert_eq!(page.sections[0].title, "Présentation");
+    assert_eq!(page.sections[0].body, "Il vit en troupeau.");
+    // Les sections suivantes sont laissées intactes
+    assert_eq!(
+        page.sections[1].body,
+        "Le cheval a accompagné l'homme dans les travaux des champs.\n\nIl est domestiqué depuis l'Antiquité."
+    );
+}
+
+#[test]
+fn liens_internes_filtres() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    assert_eq!(page.links.len(), 10);
+    assert_eq!(page.links[1], "https://fr.wikipedia.org/wiki/Aéronef");
+    assert!(page.links.contains(&"https://fr.wikipedia.org/wiki/Aile_(aéronautique)".to_string()));
+    // Ni espaces de noms (Fichier:, Portail:, Catégorie:), ni liens de modification
+    assert!(page.links.iter().all(|link| !link.trim_start_matches("https:").contains(':')));
+    assert!(page.links.iter().all(|link| !link.contains("index.php")));
+}
+
+#[test]
+fn liens_filtres_par_mot_cle() {
+    let page = parse_wikipedia_html(
+        AVION,
+        "https://fr.wikipedia.org/wiki/Avion",
+        Some("Wright"),
+        "fr",
+        &ScrapeOptions::default(),
+    );
+
+    assert_eq!(page.links, ["https://fr.wikipedia.org/wiki/Frères_Wright"]);
+}
+
+#[test]
+fn liens_espaces_de_noms_demandes() {
+    let options = ScrapeOptions {
+        include_namespaces: vec!["catégorie".to_string(), "Portail".to_string()],
+        ..ScrapeOptions::default()
+    };
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &options);
+
+    let namespaced: Vec<&str> = page
+        .links
+        .iter()
+        .map(|link| link.trim_start_matches("https://fr.wikipedia.org/wiki/"))
+        .filter(|title| title.contains(':'))
+        .collect();
+    // Les fichiers restent exclus : seuls les espaces de noms listés sont repris
+    assert!(namespaced.contains(&"Portail:Aéronautique"));
+    assert!(namespaced.iter().all(|title| title.starts_with("Catégorie:") || title.starts_with("Portail:")));
+    assert_eq!(page.links.len() - namespaced.len(), 10);
+}
+
+#[test]
+fn liens_externes_sans_wikimedia() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    // Le lien du CNRTL, cité en référence puis en lien externe, n'apparaît qu'une fois
+    assert_eq!(
+        page.external_links,
+        ["https://www.cnrtl.fr/definition/avion", "https://www.airbus.com/fr"]
+    );
+}
+
+#[test]
+fn liens_interlangues() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    // Le lien « Modifier les liens » vers Wikidata n'a pas de langue : il est ignoré
+    assert_eq!(
+        page.languages,
+        [
+            ("en".to_string(), "https://en.wikipedia.org/wiki/Airplane".to_string()),
+            ("de".to_string(), "https://de.wikipedia.org/wiki/Flugzeug".to_string()),
+            ("es".to_string(), "https://es.wikipedia.org/wiki/Avión".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn images_sans_icones() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    let urls: Vec<&str> = page.images.iter().map(|image| image.url.as_str()).collect();
+    assert_eq!(
+        urls,
+        [
+            "https://upload.wikimedia.org/wikipedia/commons/thumb/0/09/Airbus_A380.jpg/280px-Airbus_A380.jpg",
+            // Candidat 2x du srcset plutôt que la miniature de `src`
+            "https://upload.wikimedia.org/wikipedia/commons/thumb/a/a4/Wright_Flyer.jpg/440px-Wright_Flyer.jpg",
+        ]
+    );
+    assert_eq!(page.lead_image.as_deref(), Some(urls[0]));
+}
+
+#[test]
+fn legendes_des_images() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    // Image de l'infobox : ni légende ni texte alternatif
+    assert_eq!((page.images[0].caption.as_str(), page.images[0].alt.as_str()), ("", ""));
+    assert_eq!(page.images[1].caption, "Le Wright Flyer en 1903.");
+    assert_eq!(page.images[1].alt, "Le Flyer en vol");
+}
+
+#[test]
+fn limites_de_liens_et_d_images() {
+    let options = ScrapeOptions {
+        max_links: 3,
+        max_images: 1,
+        ..ScrapeOptions::default()
+    };
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &options);
+
+    assert_eq!(page.links.len(), 3);
+    assert_eq!(page.images.len(), 1);
+}
+
+#[test]
+fn taille_minimale_des_images() {
+    let options = ScrapeOptions {
+        min_image_size: 150,
+        ..ScrapeOptions::default()
+    };
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &options);
+
+    // Le Wright Flyer (220 × 140) n'atteint pas 150 pixels de haut
+    assert_eq!(page.images.len(), 1);
+    assert!(page.images[0].url.ends_with("/280px-Airbus_A380.jpg"));
+}
+
+#[test]
+fn texte_integral() {
+    let options = ScrapeOptions {
+        full_text: true,
+        ..ScrapeOptions::default()
+    };
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &options);
+
+    // Ni bandeau, ni infobox, ni sommaire, ni légende, ni portail, ni notes
+    assert_eq!(
+        page.full_text,
+        "Un avion est un aéronef de plus lourd que l'air, entraîné par un organe moteur \
+         et dont la sustentation est assurée par des voilures fixes.\n\n\
+         Le premier vol motorisé est attribué aux frères Wright en 1903.\n\n\
+         ## Histoire\n\n\
+         Les pionniers de l'aviation ont multiplié les essais au XIXe siècle.\n\n\
+         ### Premiers vols\n\n\
+         En 1890, Clément Ader décolle à bord de l'Éole.\n\n\
+         ## Conception\n\n\
+         Un avion comporte un fuselage, une voilure et un empennage.\n\n\
+         ## Notes et références\n\n\
+         ## Liens externes\n\n\
+         - Site officiel d'Airbus\n\
+         - Définition (CNRTL)\n\
+         - Avions sur Wikimedia Commons\n\
+         - Notice Wikidata"
+    );
+    // Sans l'option, le texte intégral n'est pas extrait
+    assert!(parse(AVION, "https://fr.wikipedia.org/wiki/Avion").full_text.is_empty());
+}
+
+#[test]
+fn infobox_categories_et_references() {
+    let page = parse(AVION, "https://fr.wikipedia.org/wiki/Avion");
+
+    assert_eq!(
+        page.infobox,
+        [
+            ("Type".to_string(), "Aéronef".to_string()),
+            ("Premier vol".to_string(), "17 décembre 1903".to_string()),
+        ]
+    );
+    // Les catégories cachées sont ignorées
+    assert_eq!(page.categories, ["Avion", "Aéronef"]);
+    assert_eq!(
+        page.references,
+        [
+            "Définition du CNRTL. [https://www.cnrtl.fr/definition/avion]",
+            "Jean Dupont, Histoire de l'aviation, 2001.",
+        ]
+    );
+}
+
+#[test]
+fn page_d_homonymie() {
+    let page = parse(HOMONYMIE, "https://fr.wikipedia.org/wiki/Mercure");
+
+    assert!(page.disambiguation);
+    // Une seule entrée par article, ancres et liens vers les autres projets exclus
+    assert_eq!(
+        page.disambiguation_options,
+        [
+            "https://fr.wikipedia.org/wiki/Mercure_(planète)",
+            "https://fr.wikipedia.org/wiki/Mercure_(chimie)",
+            "https://fr.wikipedia.org/wiki/Mercure_(mythologie)",
+        ]
+    );
+    assert!(page.images.is_empty());
+}
+
+#[test]
+fn page_hors_article() {
+    let page = parse(SPECIAL, "https://fr.wikipedia.org/wiki/Sp%C3%A9cial:Pages_sp%C3%A9ciales");
+
+    assert_eq!(page.title, "Sans titre");
+    assert!(page.summary.is_empty());
+    assert!(page.sections.is_empty());
+    assert!(page.links.is_empty());
+    // C'est `is_empty` qui fait échouer le scraping (`ScraperError::EmptyPage`)
+    assert!(page.is_empty());
+    assert!(!parse(AVION, "https://fr.wikipedia.org/wiki/Avion").is_empty());
+}
@@ -0,0 +1,72 @@
+//! Articles d'une exécution précédente retrouvés par `resume_index` (`--resume`)
+
+use std::fs;
+use std::path::PathBuf;
+
+use wikipedia_scraper::{
+    parse_wikipedia_html, resume_index, save_page_data, write_all_json, write_manifest,
+    MarkdownOptions, OutputFormat, PageLocation, ScrapeOptions, WikipediaPage,
+};
+
+const AVION: &str = include_str!("fixtures/avion.html");
+const URL: &str = "https://fr.wikipedia.org/wiki/Avion";
+
+fn page() -> WikipediaPage {
+    parse_wikipedia_html(AVION, URL, None, "fr", &ScrapeOptions::default())
+}
+
+fn folder(name: &str) -> PathBuf {
+    let folder = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&folder);
+    fs::create_dir_all(&folder).unwrap();
+    folder
+}
+
+#[test]
+fn reprise_sans_json_dans_les_formats() {
+    let folder = folder("reprise_markdown");
+    let folder_path = folder.to_str().unwrap();
+    let saved_paths = [format!("{}/Avion", folder_path)];
+    let base = &saved_paths[0];
+    let requested = "https://fr.wikipedia.org/wiki/Aeroplane";
+    let articles = [page()];
+
+    // `--format markdown` : pas de data.json, mais le manifeste et all.json du lot
+    fs::create_dir_all(base).unwrap();
+    let formats = [OutputFormat::Markdown];
+    save_page_data(&articles[0], PageLocation::Folder(base), &formats, &MarkdownOptions::default(), false, false)
+        .unwrap();
+    write_all_json(&articles, folder_path).unwrap();
+    write_manifest(&articles, &saved_paths, &[requested.to_string()], folder_path, None).unwrap();
+
+    let index = resume_index(folder_path, false);
+    fs::remove_dir_all(&folder).unwrap();
+
+    // Retrouvé par son URL comme par l'URL demandée, avec ses données
+    for url in [URL, requested] {
+        let saved = &index[url];
+        assert_eq!(&saved.base, base);
+        assert_eq!(saved.page.as_ref().map(|page| page.title.as_str()), Some("Avion"));
+    }
+}
+
+#[test]
+fn reprise_interrompue_en_markdown_seul() {
+    let folder = folder("reprise_interrompue");
+    let folder_path = folder.to_str().unwrap();
+    let stem = format!("{}/Avion", folder_path);
+
+    // Mode mot-clé interrompu avant le manifeste : seul `Avion.md` existe
+    let formats = [OutputFormat::Markdown];
+    save_page_data(&page(), PageLocation::Flat(&stem), &formats, &MarkdownOptions::default(), false, false)
+        .unwrap();
+
+    let index = resume_index(folder_path, true);
+    fs::remove_dir_all(&folder).unwrap();
+
+    // L'URL vient du Markdown ; sans données, l'article sera réécrit au même endroit
+    let saved = &index[URL];
+    assert_eq!(saved.base, stem);
+    assert!(saved.page.is_none());
+    assert_eq!(index.len(), 1);
+}
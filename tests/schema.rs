@@ -0,0 +1,44 @@
+//! Schéma JSON et validation des fichiers produits (`--emit-schema`, `--validate`)
+
+use wikipedia_scraper::{page_schema, parse_wikipedia_html, validate_pages_json, ScrapeOptions};
+
+const AVION: &str = include_str!("fixtures/avion.html");
+
+#[test]
+fn schema_des_articles() {
+    let schema = page_schema();
+
+    let required = schema["required"].as_array().expect("champs obligatoires");
+    assert!(required.iter().any(|field| field == "title"));
+    // Les champs ajoutés après coup restent facultatifs pour les anciens data.json
+    assert!(!required.iter().any(|field| field == "lead"));
+    assert_eq!(schema["definitions"]["Image"]["properties"]["caption"]["type"], "string");
+}
+
+#[test]
+fn validation_des_fichiers_produits() {
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &ScrapeOptions::default());
+    let json = serde_json::to_string_pretty(&page).unwrap();
+    let line = serde_json::to_string(&page).unwrap();
+
+    assert_eq!(validate_pages_json(&json).unwrap(), 1);
+    assert_eq!(validate_pages_json(&format!("[{}, {}]", json, json)).unwrap(), 2);
+    assert_eq!(validate_pages_json(&format!("{}\n{}\n", line, line)).unwrap(), 2);
+    assert!(validate_pages_json(r#"{"title": 3}"#).is_err());
+
+    // JSON indenté tronqué : erreur du document entier, pas de sa première ligne
+    let error = validate_pages_json(json.trim_end_matches('}')).unwrap_err().to_string();
+    assert!(!error.starts_with("ligne 1"), "{}", error);
+    assert!(error.contains("EOF"), "{}", error);
+}
+
+#[test]
+fn validation_selon_le_schema() {
+    let page = parse_wikipedia_html(AVION, "https://fr.wikipedia.org/wiki/Avion", None, "fr", &ScrapeOptions::default());
+    let mut json = serde_json::to_value(&page).unwrap();
+    // Images en simple URL des anciens data.json : relues par serde, mais hors schéma
+    json["images"] = serde_json::json!(["https://upload.wikimedia.org/a.jpg"]);
+
+    let error = validate_pages_json(&json.to_string()).unwrap_err().to_string();
+    assert!(error.contains("/images/0"), "{}", error);
+}
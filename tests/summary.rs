@@ -0,0 +1,23 @@
+//! Résumé de recherche (`RESUME_RECHERCHE.md`) produit par `generate_search_summary`
+
+use std::fs;
+
+use wikipedia_scraper::generate_search_summary;
+
+#[test]
+fn resume_sans_article() {
+    let folder = std::env::temp_dir().join(format!("resume_vide_{}", std::process::id()));
+    fs::create_dir_all(&folder).unwrap();
+    let folder_path = folder.to_str().unwrap();
+
+    generate_search_summary(&[], &[], folder_path, Some("Avion")).unwrap();
+    let summary = fs::read_to_string(folder.join("RESUME_RECHERCHE.md")).unwrap();
+    fs::remove_dir_all(&folder).unwrap();
+
+    assert!(summary.contains("**Nombre d'articles** : 0"));
+    assert!(summary.contains("*Aucun article scrapé.*"));
+    // Ni tableau des articles, ni moyenne calculée sur une liste vide (NaN)
+    assert!(!summary.lines().any(|line| line.starts_with('|')));
+    assert!(summary.contains("Moyenne sections     : N/A"));
+    assert!(!summary.contains("NaN"));
+}
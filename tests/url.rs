@@ -0,0 +1,67 @@
+//! Découpage des URLs par `parse_url` et encodage des titres et des requêtes
+
+use wikipedia_scraper::{encode_query, encode_wiki_title, parse_url, Scheme};
+
+#[test]
+fn hote_et_port() {
+    let url = parse_url("http://localhost:8080/wiki/Test").unwrap();
+
+    assert_eq!(url.scheme, Scheme::Http);
+    assert_eq!(url.host, "localhost");
+    assert_eq!(url.port, 8080);
+    assert_eq!(url.path, "/wiki/Test");
+}
+
+#[test]
+fn ipv6_avec_port() {
+    let url = parse_url("[::1]:8080/wiki/Test").unwrap();
+
+    // Sans schéma, HTTPS ; les crochets restent dans l'hôte pour le header Host
+    assert_eq!(url.scheme, Scheme::Https);
+    assert_eq!(url.host, "[::1]");
+    assert_eq!(url.port, 8080);
+    assert_eq!(url.path, "/wiki/Test");
+    assert_eq!(url.to_string(), "https://[::1]:8080/wiki/Test");
+}
+
+#[test]
+fn ipv6_port_par_defaut() {
+    let url = parse_url("http://[2001:db8::1]/wiki/Test").unwrap();
+
+    assert_eq!(url.host, "[2001:db8::1]");
+    assert_eq!(url.port, 80);
+    assert_eq!(url.to_string(), "http://[2001:db8::1]/wiki/Test");
+}
+
+#[test]
+fn ipv6_invalide() {
+    assert!(parse_url("http://[::1/wiki/Test").is_err());
+    assert!(parse_url("http://[::1]x/wiki/Test").is_err());
+    assert!(parse_url("http://[::1]:http/wiki/Test").is_err());
+    assert!(parse_url("http://[]:8080/").is_err());
+}
+
+#[test]
+fn titre_accentue_et_cjk() {
+    // Encodage octet UTF-8 par octet UTF-8
+    assert_eq!(encode_wiki_title("Café"), "Caf%C3%A9");
+    assert_eq!(encode_wiki_title("東京"), "%E6%9D%B1%E4%BA%AC");
+    assert_eq!(encode_query("été"), "%C3%A9t%C3%A9");
+}
+
+#[test]
+fn espaces_titre_et_requete() {
+    assert_eq!(encode_wiki_title("Tour Eiffel"), "Tour_Eiffel");
+    assert_eq!(encode_query("tour eiffel"), "tour+eiffel");
+    // Un « + » littéral ne doit pas être lu comme un espace
+    assert_eq!(encode_query("C++ 20"), "C%2B%2B+20");
+}
+
+#[test]
+fn caracteres_reserves() {
+    assert_eq!(encode_wiki_title("AC/DC"), "AC%2FDC");
+    assert_eq!(encode_wiki_title("Q&A #1?"), "Q%26A_%231%3F");
+    assert_eq!(encode_query("a&b=c#d/e?"), "a%26b%3Dc%23d%2Fe%3F");
+    // Caractères non réservés laissés tels quels
+    assert_eq!(encode_query("a-b_c.d~e"), "a-b_c.d~e");
+}